@@ -3,12 +3,225 @@
 //!
 //! Project management is easy! Time management less so.
 
+use std::fmt;
+use std::fmt::Write;
 use std::fs::read_to_string;
+use std::str::FromStr;
 
 use advent_2025::{AdventError, Puzzle};
 
-#[derive(Clone, Debug)]
-struct Safe(Vec<i16>);
+/// The number of positions on the dial, unless told otherwise.
+const DIAL_SIZE: u64 = 100;
+/// Where the dial starts, unless told otherwise.
+const DIAL_START: u64 = 50;
+/// The biggest dial to tabulate every start position of, which keeps the table
+/// to a few dozen megabytes.
+const MAX_TABLE_SIZE: u64 = 1 << 20;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Safe {
+    /// The rotations, positive to the right and negative to the left.
+    ops: Vec<i64>,
+    /// The number of positions on the dial, numbered from 0.
+    size: u64,
+    /// The position the dial starts at.
+    start: u64,
+}
+
+impl Safe {
+    /// Use a dial with `size` positions, starting at `start`.
+    fn with_dial(mut self, size: u64, start: u64) -> Result<Self, AdventError> {
+        if start >= size {
+            return Err(AdventError::Data(format!(
+                "dial start {0} is not on a dial of size {1}",
+                start, size
+            )));
+        }
+        self.size = size;
+        self.start = start;
+        Ok(self)
+    }
+
+    /// Find both passwords in a single pass over the rotations.
+    ///
+    /// The first is the number of times the dial stops on 0, and the
+    /// second is the number of times it points at 0 at all, including
+    /// while it's still turning.
+    fn passwords(&self) -> (u128, u128) {
+        self.timeline().fold((0, 0), |(stops, clicks), step| {
+            let stops = if step.stopped_on_zero {
+                stops + 1
+            } else {
+                stops
+            };
+            (stops, clicks + u128::from(step.crossings))
+        })
+    }
+
+    /// Follow the dial through every rotation, one step at a time.
+    fn timeline(&self) -> impl Iterator<Item = Step> + '_ {
+        self.ops.iter().scan(self.start, |dial, &op| {
+            let before = *dial;
+            let (crossings, after) = adjust_dial(self.size, before, op);
+            *dial = after;
+            Some(Step {
+                op,
+                before,
+                after,
+                crossings,
+                stopped_on_zero: after == 0,
+            })
+        })
+    }
+
+    /// Write out the whole timeline, with one entry per rotation.
+    fn export_timeline(&self, format: Export) -> String {
+        let mut out = String::new();
+        let steps = self.timeline();
+        // Writing to a String never fails, so the results are ignored.
+        match format {
+            Export::Csv => {
+                out.push_str("op,before,after,crossings,stopped_on_zero\n");
+                for step in steps {
+                    let _ = writeln!(
+                        out,
+                        "{0},{1},{2},{3},{4}",
+                        step.op, step.before, step.after, step.crossings, step.stopped_on_zero
+                    );
+                }
+            }
+            Export::Json => {
+                out.push('[');
+                for (idx, step) in steps.enumerate() {
+                    let sep = if idx > 0 { "," } else { "" };
+                    let _ = write!(
+                        out,
+                        "{0}\n  {{\"op\": {1}, \"before\": {2}, \"after\": {3}, \"crossings\": {4}, \"stopped_on_zero\": {5}}}",
+                        sep, step.op, step.before, step.after, step.crossings, step.stopped_on_zero
+                    );
+                }
+                out.push_str("\n]\n");
+            }
+        }
+        out
+    }
+
+    /// Find both passwords for every position the dial could start at.
+    ///
+    /// Rather than turning the dial once per start, this follows how far the
+    /// dial has turned in total. Where that lands on 0 only depends on the
+    /// start modulo the dial size, so every start is tallied in the same pass.
+    fn every_start(&self) -> Result<StartTable, AdventError> {
+        if self.size > MAX_TABLE_SIZE {
+            return Err(AdventError::Data(format!(
+                "dial of size {0} is too big to tabulate, the most is {1}",
+                self.size, MAX_TABLE_SIZE
+            )));
+        }
+        let size = self.size as usize;
+        let modulus = i128::from(self.size);
+
+        let mut stops = vec![0u128; size];
+        // How many more partial turns pass 0 from each start than the start before it.
+        let mut changes = vec![0i64; size + 1];
+        // Whole turns around the dial, which pass 0 from every start.
+        let mut laps = 0u128;
+        // How far the dial has turned in total so far.
+        let mut turned = 0i128;
+        for &op in &self.ops {
+            let distance = i128::from(op.unsigned_abs());
+            laps += (distance / modulus) as u128;
+
+            // The dial points at `start + m` for every `m` from `first` up to
+            // `first + partial - 1` beyond the whole laps, which is 0 for one
+            // run of starts that wraps around the dial.
+            let partial = distance % modulus;
+            if partial > 0 {
+                let first = if op >= 0 {
+                    turned + 1
+                } else {
+                    turned - distance
+                };
+                let low = (-(first + partial - 1)).rem_euclid(modulus) as usize;
+                let high = low + partial as usize;
+                changes[low] += 1;
+                if high <= size {
+                    changes[high] -= 1;
+                } else {
+                    changes[size] -= 1;
+                    changes[0] += 1;
+                    changes[high - size] -= 1;
+                }
+            }
+
+            turned += i128::from(op);
+            stops[(-turned).rem_euclid(modulus) as usize] += 1;
+        }
+
+        let passes = changes[..size].iter().scan(0i64, |passes, change| {
+            *passes += change;
+            Some(laps + *passes as u128)
+        });
+        Ok(StartTable(stops.into_iter().zip(passes).collect()))
+    }
+}
+
+/// What happened during a single rotation of the dial.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Step {
+    /// The rotation, positive to the right and negative to the left.
+    op: i64,
+    /// Where the dial was before the rotation.
+    before: u64,
+    /// Where the dial stopped after the rotation.
+    after: u64,
+    /// How many times the dial pointed at 0 during the rotation,
+    /// counting where it stopped but not where it started.
+    crossings: u64,
+    /// Whether the dial stopped on 0.
+    stopped_on_zero: bool,
+}
+
+/// The formats a timeline can be exported in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Export {
+    Csv,
+    Json,
+}
+
+impl FromStr for Export {
+    type Err = AdventError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "csv" => Ok(Export::Csv),
+            "json" => Ok(Export::Json),
+            _ => Err(AdventError::Parse(format!(
+                "invalid export format {0}",
+                format
+            ))),
+        }
+    }
+}
+
+/// Both passwords for every start position of the dial, indexed by the start.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct StartTable(Vec<(u128, u128)>);
+
+impl fmt::Display for StartTable {
+    /// Write one row per start position, with a header.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{0:>8} {1:>20} {2:>20}",
+            "start", "stopped on 0", "passed 0"
+        )?;
+        for (start, (stops, passes)) in self.0.iter().enumerate() {
+            writeln!(f, "{0:>8} {1:>20} {2:>20}", start, stops, passes)?;
+        }
+        Ok(())
+    }
+}
 
 impl Puzzle for Safe {
     /// Puzzle input consists of a series of directions.
@@ -16,97 +229,149 @@ impl Puzzle for Safe {
     /// A direction takes the form of `Xnn` where X is
     /// either `L` or `R` and `nn` is an integer.
     fn parse_input(file: &str) -> Result<Self, AdventError> {
-        let ops: Vec<i16> = file
+        let ops = file
             .lines()
             .map(|line| {
-                let (dir, magnitude) = line.split_at(1);
-                let dir = match dir {
-                    "L" => false,
-                    "R" => true,
-                    _ => return Err(AdventError::Parse("invalid direction".to_string())),
+                let Some((dir, magnitude)) = line.split_at_checked(1) else {
+                    return Err(AdventError::Parse(format!("invalid direction {0}", line)));
+                };
+                // Magnitudes are checked to fit, rather than wrapping around.
+                let magnitude = magnitude.parse::<i64>().ok().filter(|m| *m >= 0);
+                let Some(magnitude) = magnitude else {
+                    return Err(AdventError::Parse(format!(
+                        "invalid magnitude of rotation {0}",
+                        line
+                    )));
                 };
-
-                let magnitude = magnitude
-                    .parse::<u32>()
-                    .map_err(|_| AdventError::Parse("invalid magnitude of rotation".to_string()))?;
-                // The dial only has 100 numbers, so we don't care about anything
-                // above the hundreds.
-                // Edit: never mind, part 2 does actually care about that.
-                // let magnitude = magnitude.rem_euclid(100);
 
                 match dir {
-                    true => Ok(magnitude as i16),
-                    false => Ok(-(magnitude as i16)),
+                    "R" => Ok(magnitude),
+                    "L" => Ok(-magnitude),
+                    _ => Err(AdventError::Parse(format!("invalid direction {0}", line))),
                 }
             })
             .collect::<Result<_, _>>()?;
-        Ok(Safe(ops))
+        Ok(Safe {
+            ops,
+            size: DIAL_SIZE,
+            start: DIAL_START,
+        })
     }
 
     /// Find the password.
     ///
-    /// The safe starts at 50. The password is the number of times
-    /// the combination lands on 0. (Passing 0 during a rotation does
-    /// not count, it must come to a stop on 0.)
+    /// The password is the number of times the combination
+    /// lands on 0. (Passing 0 during a rotation does not count,
+    /// it must come to a stop on 0.)
     fn part_one(&self) -> Result<String, AdventError> {
-        let result = self.0.iter().fold((0, 50), |(counter, dial), op| {
-            let dial = dial + op;
-            let dial = dial % 100; // Dial only has 100 numbers.
-
-            let counter = match dial {
-                0 => counter + 1,
-                _ => counter,
-            };
-
-            (counter, dial)
-        });
-        Ok(result.0.to_string())
+        Ok(self.passwords().0.to_string())
     }
 
     /// Find the password under the updated protocol.
     ///
-    /// So now I can't actually modulo things because I
-    /// need to care about the number of times zero is passed
-    /// period. Gosh dang it...
+    /// Now every time the dial points at 0 counts,
+    /// whether or not it stops there.
     fn part_two(&self) -> Result<String, AdventError> {
-        let result = self.0.iter().fold((0, 50), |(counter, dial), op| {
-            let (ticks, dial) = adjust_dial(dial, *op);
-
-            // Check the number of times the dial went past 0.
-            let counter = counter + ticks;
-
-            (counter, dial)
-        });
-        Ok(result.0.to_string())
+        Ok(self.passwords().1.to_string())
     }
 }
 
-fn adjust_dial(dial: i16, op: i16) -> (i16, i16) {
-    let counter = (op / 100).abs();
-    let op = op % 100;
-    let new_dial = dial + op;
+impl fmt::Display for Safe {
+    /// Write the directions back out, one per line.
+    ///
+    /// The dial itself isn't part of the input, so only the directions are written.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for op in &self.ops {
+            let dir = if *op < 0 { 'L' } else { 'R' };
+            writeln!(f, "{0}{1}", dir, op.unsigned_abs())?;
+        }
+        Ok(())
+    }
+}
 
-    // Check to see if this actually moved us over the limit.
-    let counter = if dial != 0 && !(1..99).contains(&new_dial) {
-        counter + 1
+/// Turn a dial with `size` positions from `dial` by `op`.
+///
+/// Returns the number of times the dial points at 0 during the turn
+/// (counting where it stops, but not where it started) and where it stops.
+fn adjust_dial(size: u64, dial: u64, op: i64) -> (u64, u64) {
+    // Everything fits in an i128, however big the dial and the turn.
+    let (size, dial) = (i128::from(size), i128::from(dial));
+    let distance = i128::from(op.unsigned_abs());
+    let counter = if op >= 0 {
+        (dial + distance) / size
+    } else if dial == 0 {
+        distance / size
+    } else if distance >= dial {
+        // The first pass over 0 is `dial` clicks away, then every `size` clicks after.
+        (distance - dial) / size + 1
     } else {
-        counter
+        0
     };
-    let dial = new_dial.rem_euclid(100);
+    let dial = (dial + i128::from(op)).rem_euclid(size);
 
-    (counter, dial)
+    (
+        u64::try_from(counter).expect("counter fits in a u64"),
+        u64::try_from(dial).expect("dial is on the dial"),
+    )
 }
 
 fn main() -> Result<(), AdventError> {
     let file = read_to_string("src/input/puzzle01.txt")?;
 
-    let data = Safe::parse_input(&file)?;
-
-    println!("The password is {0}", data.part_one().unwrap());
-    println!(
-        "The password under proper protocol is {0}",
-        data.part_two().unwrap()
-    );
+    // The dial size and start can be given on the command line, otherwise
+    // it's the usual 100 position dial starting at 50. Before them,
+    // `--every-start` prints the passwords for every start position, or
+    // `--timeline=csv` or `--timeline=json` prints every step of the dial.
+    let mut args = std::env::args().skip(1).peekable();
+    let mut every_start = false;
+    let mut timeline = None;
+    while let Some(flag) = args.next_if(|arg| arg.starts_with("--")) {
+        match flag.split_once('=') {
+            None if flag == "--every-start" => every_start = true,
+            Some(("--timeline", format)) => timeline = Some(format.parse::<Export>()?),
+            _ => return Err(AdventError::Parse(format!("invalid flag {0}", flag))),
+        }
+    }
+    let mut args = args.map(|arg| {
+        arg.parse::<u64>()
+            .map_err(|_| AdventError::Parse(format!("invalid dial setting {0}", arg)))
+    });
+    let size = args.next().transpose()?.unwrap_or(DIAL_SIZE);
+    let start = args.next().transpose()?.unwrap_or(DIAL_START);
+
+    let data = Safe::parse_input(&file)?.with_dial(size, start)?;
+
+    let export = match (every_start, timeline) {
+        (true, Some(_)) => {
+            return Err(AdventError::Parse(
+                "--every-start and --timeline can't be printed together".to_string(),
+            ))
+        }
+        (true, None) => Some(data.every_start()?.to_string()),
+        (false, Some(format)) => Some(data.export_timeline(format).to_string()),
+        (false, None) => None,
+    };
+    let passwords = [
+        format!("The password is {0}", data.part_one()?),
+        format!(
+            "The password under proper protocol is {0}",
+            data.part_two()?
+        ),
+    ];
+    match export {
+        // Only the export goes to stdout, so it can be redirected into a file.
+        Some(export) => {
+            for line in passwords {
+                eprintln!("{0}", line);
+            }
+            print!("{0}", export);
+        }
+        None => {
+            for line in passwords {
+                println!("{0}", line);
+            }
+        }
+    }
 
     Ok(())
 }
@@ -127,7 +392,18 @@ mod test {
 
         let data = Safe::parse_input(input).expect("Could not parse input file");
 
-        assert_eq!(data.0, vec![-68, -30, 48, -5, 60, -55, -1, -99, 14, -82]);
+        assert_eq!(data.ops, vec![-68, -30, 48, -5, 60, -55, -1, -99, 14, -82]);
+    }
+
+    #[test]
+    fn render() {
+        let input = &*TEST_FILE;
+
+        let data = Safe::parse_input(input).expect("Could not parse input file");
+
+        assert_eq!(&data.to_string(), input);
+        let reparsed = Safe::parse_input(&data.to_string()).expect("Could not parse rendered file");
+        assert_eq!(reparsed, data);
     }
 
     #[test]
@@ -141,13 +417,130 @@ mod test {
         assert_eq!(answer, "3".to_string());
     }
 
+    #[test]
+    fn parse_wide() {
+        let data =
+            Safe::parse_input("R40000\nL9223372036854775807\n").expect("Could not parse input");
+        assert_eq!(data.ops, vec![40000, -i64::MAX]);
+
+        // These used to wrap around silently.
+        assert!(Safe::parse_input("R9223372036854775808\n").is_err());
+        assert!(Safe::parse_input("L-5\n").is_err());
+        assert!(Safe::parse_input("\n").is_err());
+    }
+
     #[test]
     fn test_adjust() {
-        assert_eq!(adjust_dial(50, 1000), (10, 50));
-        assert_eq!(adjust_dial(50, -68), (1, 82));
-        assert_eq!(adjust_dial(52, 48), (1, 0)); // Ends at zero, ticks counter.
-        assert_eq!(adjust_dial(0, -5), (0, 95)); // Starts at zero, does NOT tick counter.
-        assert_eq!(adjust_dial(55, -55), (1, 0)); // Account for hitting zero from the left.
+        assert_eq!(adjust_dial(100, 50, 1000), (10, 50));
+        assert_eq!(adjust_dial(100, 50, -68), (1, 82));
+        assert_eq!(adjust_dial(100, 52, 48), (1, 0)); // Ends at zero, ticks counter.
+        assert_eq!(adjust_dial(100, 0, -5), (0, 95)); // Starts at zero, does NOT tick counter.
+        assert_eq!(adjust_dial(100, 55, -55), (1, 0)); // Account for hitting zero from the left.
+        assert_eq!(adjust_dial(100, 0, -200), (2, 0));
+        assert_eq!(adjust_dial(100, 50, 40000), (400, 50));
+        assert_eq!(adjust_dial(7, 3, -11), (2, 6));
+        assert_eq!(adjust_dial(1, 0, i64::MIN), (1 << 63, 0));
+        assert_eq!(
+            adjust_dial(u64::MAX, u64::MAX - 1, i64::MAX),
+            (1, i64::MAX as u64 - 1)
+        );
+    }
+
+    #[test]
+    fn other_dials() {
+        let data = Safe::parse_input(&TEST_FILE).expect("Could not parse input file");
+
+        // A dial of size 1 points at zero after every click.
+        let tiny = data.clone().with_dial(1, 0).expect("0 is on the dial");
+        let clicks = data.ops.iter().map(|op| op.unsigned_abs() as u128).sum();
+        assert_eq!(tiny.passwords(), (data.ops.len() as u128, clicks));
+
+        // Compare against turning the dial one click at a time.
+        for (size, start) in [(100, 32), (7, 0), (7, 6), (3, 1)] {
+            let safe = data
+                .clone()
+                .with_dial(size, start)
+                .expect("start is on the dial");
+            let (mut stops, mut clicks, mut dial) = (0, 0, start as i64);
+            for op in &safe.ops {
+                for _ in 0..op.unsigned_abs() {
+                    dial = (dial + op.signum()).rem_euclid(size as i64);
+                    clicks += u128::from(dial == 0);
+                }
+                stops += u128::from(dial == 0);
+            }
+            assert_eq!(safe.passwords(), (stops, clicks));
+        }
+        assert!(data.with_dial(10, 10).is_err());
+    }
+
+    #[test]
+    fn timeline() {
+        let data = Safe::parse_input(&TEST_FILE).expect("Could not parse input file");
+
+        let steps = data.timeline().collect::<Vec<_>>();
+        assert_eq!(steps.len(), data.ops.len());
+        assert_eq!(
+            steps[0],
+            Step {
+                op: -68,
+                before: 50,
+                after: 82,
+                crossings: 1,
+                stopped_on_zero: false
+            }
+        );
+        assert!(steps.windows(2).all(|pair| pair[0].after == pair[1].before));
+        assert_eq!(steps.iter().filter(|step| step.stopped_on_zero).count(), 3);
+
+        let csv = data.export_timeline(Export::Csv);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("op,before,after,crossings,stopped_on_zero")
+        );
+        assert_eq!(lines.next(), Some("-68,50,82,1,false"));
+        assert_eq!(lines.count(), 9);
+
+        let json = data.export_timeline(Export::Json);
+        assert!(json.starts_with(
+            "[\n  {\"op\": -68, \"before\": 50, \"after\": 82, \"crossings\": 1, \"stopped_on_zero\": false},\n"
+        ));
+        assert!(json.ends_with("}\n]\n"));
+        assert_eq!(
+            Safe::parse_input("")
+                .map(|safe| safe.export_timeline(Export::Json))
+                .ok(),
+            Some("[\n]\n".to_string())
+        );
+    }
+
+    #[test]
+    fn every_start() {
+        let data = Safe::parse_input(&TEST_FILE).expect("Could not parse input file");
+
+        for size in [100, 7, 1] {
+            let table = data
+                .clone()
+                .with_dial(size, 0)
+                .and_then(|safe| safe.every_start())
+                .expect("could not make table");
+            assert_eq!(table.0.len(), size as usize);
+            for start in 0..size {
+                let safe = data
+                    .clone()
+                    .with_dial(size, start)
+                    .expect("start is on the dial");
+                assert_eq!(table.0[start as usize], safe.passwords());
+            }
+        }
+
+        let table = data.every_start().expect("could not make table");
+        assert_eq!(table.0[50], (3, 6));
+        assert_eq!(table.to_string().lines().count(), 101);
+
+        let huge = data.with_dial(4_000_000_000, 0).expect("0 is on the dial");
+        assert!(huge.every_start().is_err());
     }
 
     #[test]
@@ -3,11 +3,14 @@
 //!
 //! Somebody has been having "fun" with our product lines.
 
+use std::cmp;
+use std::fmt;
 use std::fs::read_to_string;
 
 use advent_2025::{AdventError, Puzzle};
+use itertools::Itertools;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Ranges(Vec<(u64, u64)>);
 
 impl Puzzle for Ranges {
@@ -49,11 +52,10 @@ impl Puzzle for Ranges {
 
     /// Find all the bad ids and sum them.
     fn part_one(&self) -> Result<String, AdventError> {
-        let bad_ids: u64 = self
+        let bad_ids: u128 = self
             .0
             .iter()
-            .map(|&(one, two)| one..=two)
-            .map(|range| range.filter(|&id| check_id(id)).sum::<u64>())
+            .map(|&(one, two)| sum_repeated(one, two, Repeats::Twice))
             .sum();
         Ok(bad_ids.to_string())
     }
@@ -62,59 +64,159 @@ impl Puzzle for Ranges {
     ///
     /// Oh Eric WHY...
     fn part_two(&self) -> Result<String, AdventError> {
-        let bad_ids = self
+        let bad_ids: u128 = self
             .0
             .iter()
-            .map(|&(one, two)| one..=two)
-            .map(|range| range.filter(|&id| check_repeats(id)).sum::<u64>())
-            .sum::<u64>();
+            .map(|&(one, two)| sum_repeated(one, two, Repeats::AtLeastTwice))
+            .sum();
         Ok(bad_ids.to_string())
     }
 }
 
-/// Detect a bad ID.
+impl fmt::Display for Ranges {
+    /// Write the ranges back out as a single comma separated line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (one, two)) in self.0.iter().enumerate() {
+            if idx != 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{0}-{1}", one, two)?;
+        }
+        writeln!(f)
+    }
+}
+
+/// The most digits a product ID can have.
+const MAX_DIGITS: u32 = u64::MAX.ilog10() + 1;
+
+/// How many times a block of digits has to repeat to make a bad ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Repeats {
+    /// Exactly twice, like `123123`.
+    Twice,
+    /// Any number of times, like `123123` or `121212`.
+    AtLeastTwice,
+}
+
+/// Returns what to multiply a `period` digit block by to repeat it out to `len` digits.
 ///
-/// A bad ID is any number that can be expressed
-/// as a string of the form `XX` (that is, a reptition
-/// of some pattern of digits.)
-fn check_id(id: u64) -> bool {
-    let id = id.to_string();
-    let len = id.len();
-    // We're looking for halves
-    // so if it doesn't divide by 2,
-    // we don't need to bother.
-    if len % 2 != 0 {
-        return false;
-    }
-    let index = len / 2;
-    let (one, two) = id.split_at(index);
-    one == two
+/// For a 2 digit block and 6 digits that's `10101`, so `12 * 10101 = 121212`.
+fn repeater(len: u32, period: u32) -> u128 {
+    (10u128.pow(len) - 1) / (10u128.pow(period) - 1)
+}
+
+/// Returns the range of `period` digit blocks which repeat out to `len` digit
+/// numbers between `lo` and `hi`, which may be empty.
+fn blocks(lo: u64, hi: u64, len: u32, period: u32) -> (u128, u128) {
+    let repeater = repeater(len, period);
+    let first = cmp::max(10u128.pow(period - 1), u128::from(lo).div_ceil(repeater));
+    let last = cmp::min(10u128.pow(period) - 1, u128::from(hi) / repeater);
+    (first, last)
 }
 
-/// Detect a repetitive ID.
+/// Sum every `len` digit number between `lo` and `hi` made of a `period` digit block.
+fn sum_with_period(lo: u64, hi: u64, len: u32, period: u32) -> u128 {
+    let (first, last) = blocks(lo, hi, len, period);
+    if first > last {
+        return 0;
+    }
+    // The blocks are consecutive, so their sum is the usual arithmetic series.
+    repeater(len, period) * ((first + last) * (last - first + 1) / 2)
+}
+
+/// Returns the distinct prime factors of a (small) number.
+fn prime_factors(mut n: u32) -> Vec<u32> {
+    let mut factors = Vec::new();
+    let mut factor = 2;
+    while n > 1 {
+        if n.is_multiple_of(factor) {
+            factors.push(factor);
+            while n.is_multiple_of(factor) {
+                n /= factor;
+            }
+        }
+        factor += 1;
+    }
+    factors
+}
+
+/// Sum every bad ID between `lo` and `hi`, without looking at every ID in between.
 ///
-/// A repetitive ID is anything that matches
-/// the regex `(\d+)\1+`. Careful viewers may
-/// note that the `regex` crate explicitly does not
-/// provide backreferences. AAAAAAAAAAAAAAAA.
-fn check_repeats(id: u64) -> bool {
-    let id = id.to_string();
-    let digits = id.chars().collect::<Vec<_>>();
-    let len = digits.len();
-    // We stop at `len / 2` because that's the
-    // longest string which has a chance of showing
-    // up multiple times in our ID. Anything longer
-    // only fits once.
-    //
-    // This is an inclusive range in case we're dealing
-    // with a two digit number where both ends of the
-    // range wind up being the exact same.
-    (1..=len / 2).any(|len| {
-        let first = &digits[..len];
-        digits.chunks(len).all(|chunk| chunk == first)
+/// For each number of digits the bad IDs are a block of digits times a
+/// repeater like `10101`, so each period only needs one arithmetic series.
+/// Some IDs have more than one period (`111111` is `1`, `11` and `111`
+/// repeated), but every period of a `len` digit ID is a multiple of its
+/// smallest one. So every bad ID repeats a block of `len / p` digits for some
+/// prime `p` dividing `len`, and counting those periods with
+/// inclusion-exclusion counts every bad ID exactly once.
+fn sum_repeated(lo: u64, hi: u64, repeats: Repeats) -> u128 {
+    let mut sum = 0;
+    for len in 2..=MAX_DIGITS {
+        match repeats {
+            Repeats::Twice if len.is_multiple_of(2) => {
+                sum += sum_with_period(lo, hi, len, len / 2);
+            }
+            Repeats::Twice => {}
+            Repeats::AtLeastTwice => {
+                let primes = prime_factors(len);
+                let (mut added, mut removed) = (0, 0);
+                for subset in 1..1u32 << primes.len() {
+                    let (product, count) = primes
+                        .iter()
+                        .enumerate()
+                        .filter(|(idx, _)| subset & (1 << idx) != 0)
+                        .fold((1, 0), |(product, count), (_, prime)| {
+                            (product * prime, count + 1)
+                        });
+                    let part = sum_with_period(lo, hi, len, len / product);
+                    if count % 2 == 1 {
+                        added += part;
+                    } else {
+                        removed += part;
+                    }
+                }
+                sum += added - removed;
+            }
+        }
+    }
+    sum
+}
+
+/// Iterate over every bad ID between `lo` and `hi` in ascending order,
+/// without looking at every ID in between.
+#[allow(dead_code)] // Only the sums are printed, so main doesn't need the IDs themselves.
+fn repeated_ids(lo: u64, hi: u64, repeats: Repeats) -> impl Iterator<Item = u64> {
+    (2..=MAX_DIGITS).flat_map(move |len| {
+        let periods = match repeats {
+            Repeats::Twice if len.is_multiple_of(2) => vec![len / 2],
+            Repeats::Twice => vec![],
+            Repeats::AtLeastTwice => (1..len).filter(|p| len.is_multiple_of(*p)).collect(),
+        };
+        // Each period gives its IDs in order, so merging them keeps every
+        // `len` digit ID in order, and all of those come before the longer ones.
+        periods
+            .into_iter()
+            .map(move |period| {
+                let (first, last) = blocks(lo, hi, len, period);
+                (first..=last)
+                    // An ID only has one smallest period, so skipping blocks that
+                    // repeat themselves means no ID comes out twice.
+                    .filter(move |&block| repeats == Repeats::Twice || !is_repeated(block, period))
+                    .map(move |block| {
+                        u64::try_from(block * repeater(len, period)).expect("IDs are at most hi")
+                    })
+            })
+            .kmerge()
     })
 }
 
+/// Returns whether a `len` digit block is itself a repeat of a shorter block.
+fn is_repeated(block: u128, len: u32) -> bool {
+    (1..len)
+        .filter(|period| len.is_multiple_of(*period))
+        .any(|period| block.is_multiple_of(repeater(len, period)))
+}
+
 fn main() -> Result<(), AdventError> {
     let file = read_to_string("src/input/puzzle02.txt")?;
 
@@ -137,12 +239,22 @@ mod test {
 
     #[test]
     fn parse_input() {
-        let data = Ranges::parse_input(&*TEST_INPUT).expect("Could not parse test input");
+        let data = Ranges::parse_input(&TEST_INPUT).expect("Could not parse test input");
 
         assert_eq!(data.0.len(), 11);
         assert_eq!(data.0[0], (11, 22));
     }
 
+    #[test]
+    fn render() {
+        let data = Ranges::parse_input(&TEST_INPUT).expect("Could not parse test input");
+
+        assert_eq!(data.to_string(), *TEST_INPUT);
+        let reparsed =
+            Ranges::parse_input(&data.to_string()).expect("Could not parse rendered input");
+        assert_eq!(reparsed, data);
+    }
+
     #[test]
     fn part_one() {
         let data = Ranges::parse_input(&TEST_INPUT).expect("Could not parse test input");
@@ -159,9 +271,89 @@ mod test {
         assert_eq!(answer, "4174379265");
     }
 
+    /// Detect a bad ID.
+    ///
+    /// A bad ID is any number that can be expressed
+    /// as a string of the form `XX` (that is, a reptition
+    /// of some pattern of digits.)
+    fn check_id(id: u64) -> bool {
+        let id = id.to_string();
+        let len = id.len();
+        // We're looking for halves
+        // so if it doesn't divide by 2,
+        // we don't need to bother.
+        if !len.is_multiple_of(2) {
+            return false;
+        }
+        let index = len / 2;
+        let (one, two) = id.split_at(index);
+        one == two
+    }
+
+    /// Detect a repetitive ID.
+    ///
+    /// A repetitive ID is anything that matches
+    /// the regex `(\d+)\1+`. Careful viewers may
+    /// note that the `regex` crate explicitly does not
+    /// provide backreferences. AAAAAAAAAAAAAAAA.
+    fn check_repeats(id: u64) -> bool {
+        let id = id.to_string();
+        let digits = id.chars().collect::<Vec<_>>();
+        let len = digits.len();
+        // We stop at `len / 2` because that's the
+        // longest string which has a chance of showing
+        // up multiple times in our ID. Anything longer
+        // only fits once.
+        //
+        // This is an inclusive range in case we're dealing
+        // with a two digit number where both ends of the
+        // range wind up being the exact same.
+        (1..=len / 2).any(|len| {
+            let first = &digits[..len];
+            digits.chunks(len).all(|chunk| chunk == first)
+        })
+    }
+
     #[test]
     fn test_repeats() {
         assert!(check_repeats(99));
         assert!(check_repeats(11111));
     }
+
+    #[test]
+    fn test_periods() {
+        assert!(is_repeated(1111, 4));
+        assert!(is_repeated(1212, 4));
+        assert!(!is_repeated(1213, 4));
+        assert_eq!(prime_factors(12), vec![2, 3]);
+        assert_eq!(repeater(6, 2), 10101);
+    }
+
+    #[test]
+    fn closed_form() {
+        for repeats in [Repeats::Twice, Repeats::AtLeastTwice] {
+            for (lo, hi) in [(1, 200_000), (95, 115), (998, 1012), (1_000_000, 1_300_000)] {
+                let slow = (lo..=hi).filter(|&id| match repeats {
+                    Repeats::Twice => check_id(id),
+                    Repeats::AtLeastTwice => check_repeats(id),
+                });
+                let slow = slow.collect::<Vec<_>>();
+                let fast = repeated_ids(lo, hi, repeats).collect::<Vec<_>>();
+                assert_eq!(fast, slow);
+                let sum = slow.iter().map(|&id| u128::from(id)).sum::<u128>();
+                assert_eq!(sum_repeated(lo, hi, repeats), sum);
+            }
+        }
+
+        // The whole range of IDs takes no longer than a small one.
+        let everything = sum_repeated(1, u64::MAX, Repeats::AtLeastTwice);
+        let twice = sum_repeated(1, u64::MAX, Repeats::Twice);
+        assert!(everything > twice);
+        assert_eq!(
+            sum_repeated(1, 99_999_999_999, Repeats::Twice),
+            repeated_ids(1, 99_999_999_999, Repeats::Twice)
+                .map(u128::from)
+                .sum::<u128>()
+        );
+    }
 }
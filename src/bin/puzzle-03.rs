@@ -3,9 +3,11 @@
 //!
 //! Alright let's get our power back online!
 
+use std::fmt;
+
 use advent_2025::{read_file, AdventError, Puzzle};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 // This is never going to store more than a single digit in each cell,
 // but I want to make space for when I add them all together.
 struct Banks(Vec<Vec<u64>>);
@@ -51,6 +53,19 @@ impl Puzzle for Banks {
     }
 }
 
+impl fmt::Display for Banks {
+    /// Write each bank back out as a row of digits.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bank in &self.0 {
+            for battery in bank {
+                write!(f, "{0}", battery)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
 fn find_voltage(bank: &[u64]) -> Result<u64, AdventError> {
     let max = max_digit_position(bank)?;
     // Check whether there's anything after this position.
@@ -129,6 +144,16 @@ mod test {
         assert_eq!(data.0[0][0..9], vec![9, 8, 7, 6, 5, 4, 3, 2, 1]);
     }
 
+    #[test]
+    fn render() {
+        let data = Banks::parse_input(&TEST_INPUT).expect("could not parse input");
+
+        assert_eq!(data.to_string(), *TEST_INPUT);
+        let reparsed =
+            Banks::parse_input(&data.to_string()).expect("could not parse rendered input");
+        assert_eq!(reparsed, data);
+    }
+
     #[test]
     fn voltage_calculation() {
         let voltage = find_voltage(&[9, 8, 7, 6, 5, 4, 3, 2, 1]).unwrap();
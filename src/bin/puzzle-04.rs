@@ -3,9 +3,12 @@
 //!
 //! Cleanup on aisle everywhere!
 
-use advent_2025::{read_file, AdventError, Grid, Puzzle};
+use std::fmt;
 
-#[derive(Clone, Debug)]
+use advent_2025::grid::{Automaton, BitGrid, Grid, ADJACENT};
+use advent_2025::{read_file, AdventError, Puzzle};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Floor(Grid<bool>);
 
 impl Puzzle for Floor {
@@ -14,21 +17,14 @@ impl Puzzle for Floor {
     ///
     /// A cell has `@` if it has paper, and '.' if it does not.
     fn parse_input(file: &str) -> Result<Self, AdventError> {
-        let mut grid: Grid<_> = Grid::new(0, 0, vec![]);
-        for line in file.lines() {
-            let line = line
-                .chars()
-                .map(|ch| match ch {
-                    '@' => Ok(true),
-                    '.' => Ok(false),
-                    ch => Err(AdventError::Parse(format!(
-                        "invalid floor character {0}",
-                        ch
-                    ))),
-                })
-                .collect::<Result<Vec<_>, AdventError>>()?;
-            grid.push_row(line);
-        }
+        let grid = Grid::parse(file, |ch| match ch {
+            '@' => Ok(true),
+            '.' => Ok(false),
+            ch => Err(AdventError::Parse(format!(
+                "invalid floor character {0}",
+                ch
+            ))),
+        })?;
         Ok(Floor(grid))
     }
 
@@ -37,12 +33,9 @@ impl Puzzle for Floor {
     /// The forklift can access any cell that has less than
     /// four obstructed neighbors.
     fn part_one(&self) -> Result<String, AdventError> {
-        let total = self
-            .0
-            .indices()
-            .filter(|&idx| self.0[idx])
-            .filter(|&idx| self.0.neighbor_cells_of(idx).filter(|&cell| *cell).count() < 4)
-            .count();
+        let floor = BitGrid::from(&self.0);
+        let reachable = floor.neighbor_counts().less_than(4);
+        let total = (&floor & &reachable).count_ones();
         Ok(total.to_string())
     }
 
@@ -51,24 +44,28 @@ impl Puzzle for Floor {
     /// This time we are iteratively clearing away the floor
     /// in the hopes that we can clear up more of the way.
     fn part_two(&self) -> Result<String, AdventError> {
-        let mut grid = self.0.clone();
-        let mut counter = 0;
-        loop {
-            let pass = grid
-                .indices()
-                .filter(|&idx| grid[idx])
-                .filter(|&idx| grid.neighbor_cells_of(idx).filter(|&cell| *cell).count() < 4)
-                .collect::<Vec<_>>();
-            // If there are no cells we can clear, our job here is done.
-            if pass.is_empty() {
-                break;
+        // Every pass clears all of the cells that are reachable at the
+        // start of it, and we keep going until there's nothing left to clear.
+        let mut floor = Automaton::new(self.0.clone(), &ADJACENT, |&cell, neighbors| {
+            cell && neighbors.iter().flatten().filter(|&&&cell| cell).count() >= 4
+        });
+        floor.run_to_fixed_point();
+        // Cells only ever get cleared, so every change is one more cleared cell.
+        let counter = floor.changes().iter().sum::<usize>();
+        Ok(counter.to_string())
+    }
+}
+
+impl fmt::Display for Floor {
+    /// Write the floor layout back out, one row per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.0.rows() {
+            for &cell in row {
+                write!(f, "{0}", if cell { '@' } else { '.' })?;
             }
-            // Otherwise add the number of cells we can reach to the counter...
-            counter += pass.len();
-            // And mark them as clear for the next pass.
-            pass.iter().for_each(|&idx| grid[idx] = false);
+            writeln!(f)?;
         }
-        Ok(counter.to_string())
+        Ok(())
     }
 }
 
@@ -103,6 +100,16 @@ mod test {
         assert_eq!(data.0.dimensions(), (10, 10));
     }
 
+    #[test]
+    fn render() {
+        let data = Floor::parse_input(&TEST_INPUT).expect("could not parse input");
+
+        assert_eq!(data.to_string(), *TEST_INPUT);
+        let reparsed =
+            Floor::parse_input(&data.to_string()).expect("could not parse rendered input");
+        assert_eq!(reparsed, data);
+    }
+
     #[test]
     fn part_one() {
         let data = Floor::parse_input(&TEST_INPUT).expect("could not parse input");
@@ -5,13 +5,19 @@
 //! this week? We don't have time to check
 //! everything by hand. Or by nose.
 
-use advent_2025::{read_file, AdventError, Puzzle};
+use std::fmt;
+use std::ops::RangeInclusive;
 
-#[derive(Clone, Debug)]
+use advent_2025::{read_file, AdventError, MultiRangeBuf, Puzzle};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Database {
-    /// A list of ranges of known good
-    /// ingredients, inclusive of both ends.
-    ranges: Vec<(u64, u64)>,
+    /// The ranges of known good ingredients,
+    /// exactly as they were listed.
+    ranges: Vec<RangeInclusive<u64>>,
+    /// Every known good ingredient, with
+    /// the ranges above merged together.
+    fresh: MultiRangeBuf<u64>,
     ingredients: Vec<u64>,
 }
 
@@ -26,7 +32,7 @@ impl Puzzle for Database {
         let (ranges, ingredients) = file
             .split_once("\n\n")
             .ok_or_else(|| AdventError::Parse("could not find ingredients list".to_string()))?;
-        
+
         let ranges = ranges
             .lines()
             .map(|line| {
@@ -39,9 +45,10 @@ impl Puzzle for Database {
                 let Ok(two) = two.parse::<u64>() else {
                     return Err(AdventError::Parse(format!("invalid ingredient {0}", two)));
                 };
-                Ok((one, two))
+                Ok(one..=two)
             })
             .collect::<Result<Vec<_>, AdventError>>()?;
+        let fresh = ranges.iter().cloned().collect();
 
         let ingredients = ingredients
             .lines()
@@ -53,6 +60,7 @@ impl Puzzle for Database {
 
         let database = Database {
             ranges,
+            fresh,
             ingredients,
         };
 
@@ -67,60 +75,31 @@ impl Puzzle for Database {
         let sum = self
             .ingredients
             .iter()
-            .filter(|&item| {
-                self.ranges
-                    .iter()
-                    .any(|&(one, two)| (one..=two).contains(item))
-            })
+            .filter(|&&item| self.fresh.contains(item))
             .count();
         Ok(sum.to_string())
     }
 
     /// Find the maximum number of possible fresh ingredients.
+    ///
+    /// The ranges are already merged, so this is just their total size.
     fn part_two(&self) -> Result<String, AdventError> {
-        let mut bounds = self.ranges.clone();
-        bounds.sort_by_key(|range| range.0); // Get all of the ranges sorted.
-
-        // Credit to AkalUstat for this technique, by the way.
-        let bounds = bounds.into_iter().fold(Vec::new(), |mut acc, range| {
-            let Some(last) = acc.last() else {
-                // The vector is empty, so we
-                // can just push what we have
-                // and be done with it.
-                acc.push(range);
-                return acc;
-            };
-            // Copy here to avoid lifetime issues.
-            let last = *last;
-
-            // If there is overlap, combine the ranges.
-            if ranges_overlap(last, range) {
-                acc.pop();
-                let range = (last.0.min(range.0), last.1.max(range.1));
-                acc.push(range);
-            } else {
-                // Otherwise we can add the new range without worry.
-                acc.push(range);
-            }
-            acc
-        });
-
-        let sum = bounds
-            .iter()
-            .map(|&(one, two)| one..=two)
-            .map(|range| range.count())
-            .sum::<usize>();
-        Ok(sum.to_string())
+        Ok(self.fresh.size().to_string())
     }
 }
 
-/// Check whether two ranges overlap.
-///
-/// This checks that the second range starts
-/// after the first one ends, or that the second
-/// range ends before the first one starts.
-fn ranges_overlap(one: (u64, u64), two: (u64, u64)) -> bool {
-    !(one.1 < two.0 || two.1 < one.0)
+impl fmt::Display for Database {
+    /// Write the ranges, a blank line, and then the ingredients.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for range in &self.ranges {
+            writeln!(f, "{0}-{1}", range.start(), range.end())?;
+        }
+        writeln!(f)?;
+        for item in &self.ingredients {
+            writeln!(f, "{0}", item)?;
+        }
+        Ok(())
+    }
 }
 
 fn main() -> Result<(), AdventError> {
@@ -149,10 +128,24 @@ mod test {
     fn parse_input() {
         let data = Database::parse_input(&TEST_INPUT).expect("could not parse input");
 
-        assert_eq!(data.ranges[0], (3, 5));
+        assert_eq!(data.ranges[0], 3..=5);
+        assert_eq!(
+            data.fresh.into_iter().collect::<Vec<_>>(),
+            vec![3..=5, 10..=20]
+        );
         assert_eq!(data.ingredients, vec![1, 5, 8, 11, 17, 32]);
     }
 
+    #[test]
+    fn render() {
+        let data = Database::parse_input(&TEST_INPUT).expect("could not parse input");
+
+        assert_eq!(data.to_string(), *TEST_INPUT);
+        let reparsed =
+            Database::parse_input(&data.to_string()).expect("could not parse rendered input");
+        assert_eq!(reparsed, data);
+    }
+
     #[test]
     fn part_one() {
         let data = Database::parse_input(&TEST_INPUT).expect("could not parse input");
@@ -4,9 +4,12 @@
 //! Hey, while you're down here,
 //! can you help with some math homework?
 
-use advent_2025::{read_file, AdventError, Grid, Puzzle};
+use std::fmt;
 
-#[derive(Clone, Debug)]
+use advent_2025::grid::Grid;
+use advent_2025::{read_file, AdventError, Puzzle};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Worksheet {
     sheet: Grid<char>,
     operations: Vec<Operation>,
@@ -49,28 +52,18 @@ impl Puzzle for Worksheet {
             .collect::<Result<Vec<_>, AdventError>>()?;
 
         // Before we assemble the grid, we need to make sure every line
-        // has the same length, or [Grid::push_row] will panic.
+        // has the same length, since a `Grid` has to be rectangular.
         // So we find the longest line...
         let Some(line_len) = sheet.iter().max_by_key(|line| line.len()) else {
             return Err(AdventError::Data("file should have operands".to_string()));
         };
-        // And get its length.
+        // And pad every line out to its length.
         let max_len = line_len.len();
-        let sheet = sheet
-            .iter()
-            // Make sure all of the lines are the same length.
-            .map(|line| {
-                let mut new_line = line.to_string();
-                for _ in 0..(max_len - line.len()) {
-                    new_line.push(' ');
-                }
-                new_line
-            })
-            // Because `Grid` nees them all the same length.
-            .fold(Grid::new(0, 0, vec![]), |mut grid, line| {
-                grid.push_row(line.chars().collect());
-                grid
-            });
+        let sheet = Grid::from_rows(
+            sheet
+                .iter()
+                .map(|line| format!("{0:1$}", line, max_len).chars().collect::<Vec<_>>()),
+        )?;
         Ok(Worksheet {
             sheet,
             operations: ops,
@@ -103,21 +96,47 @@ impl Puzzle for Worksheet {
     }
 }
 
+impl fmt::Display for Worksheet {
+    /// Write the sheet back out verbatim, followed by the operations.
+    ///
+    /// The rows of the sheet were padded out when parsing, so they
+    /// come back out padded as well. Each operation goes under the
+    /// first column of its problem, like in the input.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{0}", self.sheet)?;
+        let blank = |x: usize| self.sheet.column(x).iter().all(|&ch| ch == ' ');
+        let starts = (0..self.sheet.width()).filter(|&x| !blank(x) && (x == 0 || blank(x - 1)));
+        let mut ops = self.operations.iter().map(|op| match op {
+            Operation::Add => '+',
+            Operation::Mul => '*',
+        });
+        let mut line = vec![' '; self.sheet.width()];
+        for (x, op) in starts.zip(ops.by_ref()) {
+            line[x] = op;
+        }
+        // Any operations without a problem of their own still get written.
+        for op in ops {
+            line.extend([' ', op]);
+        }
+        writeln!(f, "{0}", line.into_iter().collect::<String>())
+    }
+}
+
 impl Worksheet {
     fn parse_one(&self) -> Result<Vec<Equation>, AdventError> {
-        let grid = self.sheet.rows().try_fold(
-            Grid::new(0, 0, vec![]),
-            |mut grid, idx| -> Result<_, AdventError> {
-                let line = self.sheet.row_iter(idx).collect::<String>();
-                let line = line
+        let rows = self
+            .sheet
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .collect::<String>()
                     .split_ascii_whitespace()
                     .map(|cell| cell.to_owned())
-                    .collect::<Vec<_>>();
-                grid.push_row(line);
-                Ok(grid)
-            },
-        )?;
-        //assert_eq!(grid.width(), self.operations.len());
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let grid = Grid::from_rows(rows)
+            .map_err(|_| AdventError::Parse("improperly formed worksheet".to_string()))?;
         if grid.width() != self.operations.len() {
             return Err(AdventError::Parse(
                 "improperly formed worksheet".to_string(),
@@ -126,11 +145,10 @@ impl Worksheet {
 
         let sheet = grid
             .columns()
-            .map(|idx| {
-                // Get the column this index corresponds to.
-                let col = grid
-                    .column_iter(idx)
-                    .cloned()
+            .enumerate()
+            .map(|(idx, column)| {
+                let col = column
+                    .iter()
                     .map(|num| {
                         num.parse::<u64>()
                             .map_err(|_| AdventError::Data(format!("invalid operand {0}", num)))
@@ -147,11 +165,14 @@ impl Worksheet {
     }
 
     fn parse_two(&self) -> Result<Vec<Equation>, AdventError> {
+        // Reading the columns top to bottom is the same as reading
+        // the rows of the transposed sheet left to right.
         let columns = self
             .sheet
-            .columns()
-            .map(|col| self.sheet.column_iter(col).collect::<String>())
-            .map(|num| num.trim_ascii().to_owned())
+            .view()
+            .transpose()
+            .rows()
+            .map(|col| col.collect::<String>().trim_ascii().to_owned())
             .collect::<Vec<_>>();
 
         let operands = columns.split(|col| col.is_empty()).collect::<Vec<_>>();
@@ -216,6 +237,16 @@ mod test {
         );
     }
 
+    #[test]
+    fn render() {
+        let data = Worksheet::parse_input(&TEST_INPUT).expect("could not parse input file");
+
+        assert_eq!(data.to_string(), *TEST_INPUT);
+        let reparsed =
+            Worksheet::parse_input(&data.to_string()).expect("could not parse rendered file");
+        assert_eq!(reparsed, data);
+    }
+
     #[test]
     fn part_one() {
         let data = Worksheet::parse_input(&TEST_INPUT).expect("could not parse input file");
@@ -3,11 +3,13 @@
 //!
 //! Lasers are fun and effective!
 
-use advent_2025::{read_file, AdventError, Grid, GridIndex, Puzzle};
+use advent_2025::grid::{Grid, Offset};
+use advent_2025::{read_file, AdventError, Puzzle};
 
 use std::collections::HashSet;
+use std::fmt;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Manifold(Grid<Cell>);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -17,6 +19,9 @@ enum Cell {
     Empty,
 }
 
+/// Where a beam goes after hitting a splitter.
+const SPLIT: [Offset; 2] = [(-1, 0), (1, 0)];
+
 impl Puzzle for Manifold {
     /// Input consists of a tachyon manifold.
     ///
@@ -25,22 +30,12 @@ impl Puzzle for Manifold {
     /// representing empty space, or a caret (`|`) representing
     /// a beam splitter.
     fn parse_input(file: &str) -> Result<Self, AdventError> {
-        let grid = file.lines().try_fold(
-            Grid::new(0, 0, Vec::new()),
-            |mut grid, line| -> Result<_, AdventError> {
-                let line = line
-                    .chars()
-                    .map(|ch| match ch {
-                        'S' => Ok(Cell::Start),
-                        '.' => Ok(Cell::Empty),
-                        '^' => Ok(Cell::Split),
-                        err => Err(AdventError::Parse(format!("invalid cell {0}", err))),
-                    })
-                    .collect::<Result<Vec<Cell>, AdventError>>()?;
-                grid.push_row(line);
-                Ok(grid)
-            },
-        )?;
+        let grid = Grid::parse(file, |ch| match ch {
+            'S' => Ok(Cell::Start),
+            '.' => Ok(Cell::Empty),
+            '^' => Ok(Cell::Split),
+            err => Err(AdventError::Parse(format!("invalid cell {0}", err))),
+        })?;
         Ok(Manifold(grid))
     }
 
@@ -53,7 +48,8 @@ impl Puzzle for Manifold {
         // Sanity check here.
         if self
             .0
-            .row_iter(0)
+            .row(0)
+            .iter()
             .filter(|&cell| *cell == Cell::Start)
             .count()
             != 1
@@ -62,7 +58,7 @@ impl Puzzle for Manifold {
                 "first row does not have exactly one start".to_string(),
             ));
         }
-        let Some(start) = self.0.row_iter(0).position(|&cell| cell == Cell::Start) else {
+        let Some(start) = self.0.row(0).iter().position(|&cell| cell == Cell::Start) else {
             unreachable!("should have just checked for start's existence");
         };
 
@@ -72,42 +68,37 @@ impl Puzzle for Manifold {
         let mut beams = HashSet::new();
         // Add the column of our initial starting position.
         beams.insert(start);
-        let beams: Result<_, AdventError> = self
-            .0
-            .rows()
-            .skip(1) // ignore the first row
-            .try_fold((0, beams), |(splits, beams), row| {
-                let mut new_beams = beams.clone();
-                let mut splits = splits;
-                for &col in beams.iter() {
-                    match self.0.get(GridIndex::new(col, row)) {
-                        Some(Cell::Empty) => {
-                            // Nothing happens.
-                        }
-                        Some(Cell::Split) => {
-                            // The beam stops at a splitter,
-                            // so remove it from the set.
-                            new_beams.remove(&col);
-                            // Add to the split counter.
-                            splits += 1;
-
-                            // These are both sanity checks,
-                            // but they're good to have.
-                            if col != 0 {
-                                new_beams.insert(col - 1);
+        let beams: Result<_, AdventError> =
+            (1..self.0.height()) // ignore the first row
+                .try_fold((0, beams), |(splits, beams), row| {
+                    let mut new_beams = beams.clone();
+                    let mut splits = splits;
+                    for &col in beams.iter() {
+                        match self.0.get((col, row)) {
+                            Some(Cell::Empty) => {
+                                // Nothing happens.
                             }
-                            if col != self.0.width() - 1 {
-                                new_beams.insert(col + 1);
+                            Some(Cell::Split) => {
+                                // The beam stops at a splitter,
+                                // so remove it from the set.
+                                new_beams.remove(&col);
+                                // Add to the split counter.
+                                splits += 1;
+
+                                // Beams split off the side of the
+                                // manifold just disappear.
+                                for ((col, _), _) in self.0.neighbors((col, row), &SPLIT) {
+                                    new_beams.insert(col);
+                                }
                             }
+                            Some(Cell::Start) => {
+                                return Err(AdventError::Data("multiple starts found".to_string()))
+                            }
+                            None => unreachable!("escaped grid somehow {0}:{1}", row, col),
                         }
-                        Some(Cell::Start) => {
-                            return Err(AdventError::Data("multiple starts found".to_string()))
-                        }
-                        None => unreachable!("escaped grid somehow {0}:{1}", row, col),
                     }
-                }
-                Ok((splits, new_beams))
-            });
+                    Ok((splits, new_beams))
+                });
         // This is here mostly for type checking.
         let (splits, _) = beams?;
         Ok(splits.to_string())
@@ -117,7 +108,8 @@ impl Puzzle for Manifold {
     fn part_two(&self) -> Result<String, AdventError> {
         if self
             .0
-            .row_iter(0)
+            .row(0)
+            .iter()
             .filter(|&cell| *cell == Cell::Start)
             .count()
             != 1
@@ -129,7 +121,8 @@ impl Puzzle for Manifold {
 
         let init = self
             .0
-            .row_iter(0)
+            .row(0)
+            .iter()
             .map(|cell| match cell {
                 Cell::Start => Ok(1),
                 Cell::Empty => Ok(0),
@@ -137,14 +130,12 @@ impl Puzzle for Manifold {
             })
             .collect::<Result<Vec<usize>, AdventError>>()?;
 
-        let res: Result<_, AdventError> = self
-            .0
-            .rows()
-            .skip(1) // ignore the first row
+        let res: Result<_, AdventError> = (1..self.0.height()) // ignore the first row
             .try_fold(init, |totals, row| {
                 let mut next = vec![0; totals.len()];
                 self.0
-                    .row_iter(row)
+                    .row(row)
+                    .iter()
                     .enumerate()
                     .try_for_each(|(idx, &col)| {
                         match col {
@@ -155,11 +146,8 @@ impl Puzzle for Manifold {
                             }
                             Cell::Split => {
                                 next[idx] = 0;
-                                if idx != 0 {
-                                    next[idx - 1] += totals[idx];
-                                }
-                                if idx != totals.len() - 1 {
-                                    next[idx + 1] += totals[idx];
+                                for ((col, _), _) in self.0.neighbors((idx, row), &SPLIT) {
+                                    next[col] += totals[idx];
                                 }
                             }
                             Cell::Start => {
@@ -175,6 +163,24 @@ impl Puzzle for Manifold {
     }
 }
 
+impl fmt::Display for Manifold {
+    /// Write the manifold back out, one row per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.0.rows() {
+            for cell in row {
+                let ch = match cell {
+                    Cell::Start => 'S',
+                    Cell::Empty => '.',
+                    Cell::Split => '^',
+                };
+                write!(f, "{0}", ch)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
 fn main() -> Result<(), AdventError> {
     let file = read_file("src/input/puzzle07.txt")?;
 
@@ -201,6 +207,16 @@ mod test {
         assert_eq!(data.0.get((7, 0)), Some(&Cell::Start));
     }
 
+    #[test]
+    fn render() {
+        let data = Manifold::parse_input(&TEST_INPUT).expect("could not parse input file");
+
+        assert_eq!(data.to_string(), *TEST_INPUT);
+        let reparsed =
+            Manifold::parse_input(&data.to_string()).expect("could not parse rendered file");
+        assert_eq!(reparsed, data);
+    }
+
     #[test]
     fn part_one() {
         let data = Manifold::parse_input(&TEST_INPUT).expect("could not parse input file");
@@ -3,9 +3,12 @@
 //!
 //! Playing with wires is perfectly safe!
 
-use advent_2025::{read_file, AdventError, Puzzle};
+use std::fmt;
 
-#[derive(Clone, Debug)]
+use advent_2025::{AdventError, Puzzle};
+
+#[derive(Clone, Debug, PartialEq)]
+#[allow(dead_code)] // Nothing reads the input until the puzzle is solved.
 struct JunctionMap(Vec<Junction>);
 
 #[derive(Clone, Debug, PartialEq)]
@@ -55,6 +58,19 @@ impl Puzzle for JunctionMap {
     }
 }
 
+impl fmt::Display for JunctionMap {
+    /// Write the position of every junction back out.
+    ///
+    /// Connections are never part of the input, so they are not written.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for junction in &self.0 {
+            let (x, y, z) = junction.position;
+            writeln!(f, "{0},{1},{2}", x, y, z)?;
+        }
+        Ok(())
+    }
+}
+
 fn main() -> Result<(), AdventError> {
     Ok(())
 }
@@ -65,6 +81,8 @@ mod test {
 
     use std::sync::LazyLock;
 
+    use advent_2025::read_file;
+
     static TEST_INPUT: LazyLock<String> = LazyLock::new(|| {
         read_file("src/input/puzzle08-test.txt").expect("could not read input file")
     });
@@ -81,4 +99,14 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn render() {
+        let data = JunctionMap::parse_input(&TEST_INPUT).expect("could not parse input file");
+
+        assert_eq!(data.to_string(), *TEST_INPUT);
+        let reparsed =
+            JunctionMap::parse_input(&data.to_string()).expect("could not parse rendered file");
+        assert_eq!(reparsed, data);
+    }
 }
@@ -4,13 +4,15 @@
 //! No, look down at the floor! It's better entertainment
 //! than the movie they're showing here, anyway!
 
+use std::fmt;
+
 use advent_2025::{read_file, AdventError, Puzzle};
 
 use itertools::Itertools;
 
 type Point = (u64, u64);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Floor(Vec<Point>);
 
 impl Puzzle for Floor {
@@ -95,6 +97,16 @@ impl Puzzle for Floor {
     }
 }
 
+impl fmt::Display for Floor {
+    /// Write the coordinate pairs back out, one per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (x, y) in &self.0 {
+            writeln!(f, "{0},{1}", x, y)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 struct Line {
     one: Point,
@@ -143,6 +155,16 @@ mod test {
         assert_eq!(data.0[0], (7, 1));
     }
 
+    #[test]
+    fn render() {
+        let data = Floor::parse_input(&TEST_INPUT).expect("could not parse input file");
+
+        assert_eq!(data.to_string(), *TEST_INPUT);
+        let reparsed =
+            Floor::parse_input(&data.to_string()).expect("could not parse rendered file");
+        assert_eq!(reparsed, data);
+    }
+
     #[test]
     fn part_one() {
         let data = Floor::parse_input(&TEST_INPUT).expect("could not parse input file");
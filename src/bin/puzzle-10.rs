@@ -5,6 +5,7 @@
 //! North Pole instead of Silent Hill.
 
 use std::collections::{HashSet, VecDeque};
+use std::fmt;
 use std::sync::LazyLock;
 
 use advent_2025::{read_file, AdventError, Puzzle};
@@ -20,7 +21,7 @@ static MACHINE_RE: LazyLock<Regex> = LazyLock::new(|| {
     .expect("regex should compile")
 });
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[allow(dead_code)] // One of the fields is unused for part one.
 struct Machine {
     pattern: Vec<bool>,
@@ -58,7 +59,7 @@ impl Machine {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Manual(Vec<Machine>);
 
 impl Puzzle for Manual {
@@ -180,6 +181,33 @@ impl Puzzle for Manual {
     }
 }
 
+impl fmt::Display for Machine {
+    /// Write the machine back out in the same format as the manual.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pattern = self
+            .pattern
+            .iter()
+            .map(|&flag| if flag { '#' } else { '.' })
+            .collect::<String>();
+        write!(f, "[{0}] ", pattern)?;
+        for button in &self.buttons {
+            write!(f, "({0}) ", button.iter().join(","))?;
+        }
+        write!(f, "{{{0}}}", self.joltages.iter().join(","))
+    }
+}
+
+impl fmt::Display for Manual {
+    /// Write every machine specification back out, one per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for machine in &self.0 {
+            writeln!(f, "{0}", machine)?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(dead_code)] // Not used by part one anymore.
 fn find_press_count(buttons: &[usize], pattern: usize) -> Option<usize> {
     (2..).find(|k| {
         buttons
@@ -244,6 +272,16 @@ mod test {
         assert_eq!(machine.joltages, vec![3, 5, 4, 7], "incorrect joltages");
     }
 
+    #[test]
+    fn render() {
+        let data = Manual::parse_input(&TEST_INPUT).expect("could not parse input file");
+
+        assert_eq!(data.to_string(), *TEST_INPUT);
+        let reparsed =
+            Manual::parse_input(&data.to_string()).expect("could not parse rendered file");
+        assert_eq!(reparsed, data);
+    }
+
     #[test]
     fn t_interpret_pattern() {
         assert_eq!(interpret_pattern(&[false, true, true, false]), 0b0110);
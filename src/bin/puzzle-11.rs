@@ -7,6 +7,7 @@ use advent_2025::{read_file, Puzzle, AdventError};
 use petgraph::algo::has_path_connecting;
 
 use std::collections::hash_map::RandomState;
+use std::fmt;
 
 use petgraph::prelude::{DiGraph, NodeIndex};
 use petgraph::algo::simple_paths::all_simple_paths;
@@ -32,8 +33,7 @@ impl Puzzle for Rack {
 
         let mut graph: DiGraph<String, ()> = DiGraph::new();
         let nodes = connections.iter().map(|(node, _)| {
-            let node = graph.add_node(node.to_string());
-            node
+            graph.add_node(node.to_string())
         }).collect::<Vec<_>>();
         // `out` doesn't link to any nodes, so add it to the graph manually.
         graph.add_node("out".to_string());
@@ -97,6 +97,41 @@ impl Puzzle for Rack {
     }
 }
 
+impl fmt::Display for Rack {
+    /// Write the connection list back out.
+    ///
+    /// The last node is the `out` node added while parsing,
+    /// so it doesn't get a line of its own.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let listed = self.0.node_count().saturating_sub(1);
+        for node in self.0.node_indices().take(listed) {
+            // Sort so that parsing adds the edges back in the same order.
+            let mut neighbors = self.0.neighbors(node).collect::<Vec<_>>();
+            neighbors.sort();
+            write!(f, "{0}:", self.0[node])?;
+            for neighbor in neighbors {
+                write!(f, " {0}", self.0[neighbor])?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// `DiGraph` doesn't implement `PartialEq`, so compare nodes and edges by hand.
+impl PartialEq for Rack {
+    fn eq(&self, other: &Self) -> bool {
+        let edges = |rack: &Rack| {
+            rack.0
+                .raw_edges()
+                .iter()
+                .map(|edge| (edge.source(), edge.target()))
+                .collect::<Vec<_>>()
+        };
+        self.0.node_weights().eq(other.0.node_weights()) && edges(self) == edges(other)
+    }
+}
+
 impl Rack {
     fn count_paths(&self, a: NodeIndex<u32>, b: NodeIndex<u32>) -> usize {
         all_simple_paths::<Vec<_>, _, RandomState>(&self.0, a, b, 1, None).count()
@@ -131,6 +166,15 @@ mod test {
         assert_eq!(data.0.neighbors(you).count(), 2);
     }
 
+    #[test]
+    fn render() {
+        let data = Rack::parse_input(&TEST_INPUT).expect("could not parse input file");
+
+        assert_eq!(data.to_string(), *TEST_INPUT);
+        let reparsed = Rack::parse_input(&data.to_string()).expect("could not parse rendered file");
+        assert_eq!(reparsed, data);
+    }
+
     #[test]
     fn part_one() {
         let data = Rack::parse_input(&TEST_INPUT).expect("could not parse input file");
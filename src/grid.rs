@@ -0,0 +1,467 @@
+//! A two dimensional grid for AoC.
+//!
+//! This is meant to replace [simple_grid::Grid], which doesn't
+//! give easy access to whole rows or columns at a time.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::AdventError;
+
+mod automaton;
+mod bits;
+mod neighbors;
+mod regions;
+mod render;
+mod search;
+mod sparse;
+mod transform;
+
+pub use automaton::{Automaton, Update};
+pub use bits::{BitGrid, NeighborCounts};
+pub use neighbors::{Direction, Edge, Offset, ADJACENT, CARDINAL};
+pub use regions::{Region, Regions};
+pub use render::Color;
+pub use search::Paths;
+pub use sparse::{adjacent_offsets, cardinal_offsets, Bounds, SparseGrid, SparseGrid3};
+pub use transform::{Symmetry, View};
+
+/// A position in a [Grid], given as `(x, y)`.
+///
+/// `x` is the column and `y` is the row, with `(0, 0)` in the top left.
+pub type Position = (usize, usize);
+
+/// A rectangular grid of cells, stored in row-major order.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    // Invariant: cells.len() == width * height
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid out of a list of cells in row-major order.
+    ///
+    /// Fails if there aren't exactly `width * height` cells.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Result<Self, AdventError> {
+        if width.checked_mul(height) != Some(cells.len()) {
+            return Err(AdventError::Data(format!(
+                "a {0}x{1} grid cannot hold {2} cells",
+                width,
+                height,
+                cells.len()
+            )));
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Create a grid out of a series of rows.
+    ///
+    /// Fails if the rows aren't all the same length.
+    pub fn from_rows<I, R>(rows: I) -> Result<Self, AdventError>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = T>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for row in rows {
+            let before = cells.len();
+            cells.extend(row);
+            let len = cells.len() - before;
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    return Err(AdventError::Data(format!(
+                        "row {0} has length {1}, expected {2}",
+                        height, len, width
+                    )))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        Grid::new(width.unwrap_or(0), height, cells)
+    }
+
+    /// Parse a grid out of text, one row per line, converting each character.
+    pub fn parse<F>(text: &str, mut convert: F) -> Result<Self, AdventError>
+    where
+        F: FnMut(char) -> Result<T, AdventError>,
+    {
+        let rows = text
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(&mut convert)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Grid::from_rows(rows)
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `(width, height)`.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns true if the grid has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns whether a position lies inside of the grid.
+    pub fn contains(&self, (x, y): Position) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Get a reference to the cell at a position, if it exists.
+    pub fn get(&self, pos: Position) -> Option<&T> {
+        self.linear(pos).map(|idx| &self.cells[idx])
+    }
+
+    /// Get a mutable reference to the cell at a position, if it exists.
+    pub fn get_mut(&mut self, pos: Position) -> Option<&mut T> {
+        self.linear(pos).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Move a position by a signed offset.
+    ///
+    /// Returns `None` if the result would leave the grid.
+    pub fn offset(&self, (x, y): Position, (dx, dy): Offset) -> Option<Position> {
+        let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Get the cell at a signed offset from a position, if it exists.
+    pub fn get_offset(&self, pos: Position, delta: Offset) -> Option<&T> {
+        self.offset(pos, delta).and_then(|pos| self.get(pos))
+    }
+
+    /// Get a single row as a slice.
+    ///
+    /// # Panics
+    /// Panics if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row {0} out of bounds", y);
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Get a single row as a mutable slice.
+    ///
+    /// # Panics
+    /// Panics if `y` is out of bounds.
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        assert!(y < self.height, "row {0} out of bounds", y);
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Iterate over every row, from top to bottom.
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// Get a view of a single column.
+    ///
+    /// # Panics
+    /// Panics if `x` is out of bounds.
+    pub fn column(&self, x: usize) -> Column<'_, T> {
+        assert!(x < self.width, "column {0} out of bounds", x);
+        Column { grid: self, x }
+    }
+
+    /// Iterate over every column, from left to right.
+    pub fn columns(&self) -> impl DoubleEndedIterator<Item = Column<'_, T>> + ExactSizeIterator {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Iterate over every position in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Iterate over every cell in row-major order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    /// Iterate mutably over every cell in row-major order.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// Iterate over every cell along with its position.
+    pub fn enumerate(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Create a new grid of the same size by converting every cell.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, convert: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(convert).collect(),
+        }
+    }
+
+    fn linear(&self, pos: Position) -> Option<usize> {
+        self.contains(pos).then(|| pos.1 * self.width + pos.0)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Create a grid where every cell has the same value.
+    ///
+    /// Panics if the grid has more cells than fit in a `usize`. Use
+    /// [Grid::try_filled] when the size comes from the input.
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        let len = width
+            .checked_mul(height)
+            .unwrap_or_else(|| panic!("a {0}x{1} grid has too many cells", width, height));
+        Grid {
+            width,
+            height,
+            cells: vec![value; len],
+        }
+    }
+
+    /// Create a grid where every cell has the same value.
+    ///
+    /// Fails if the grid is too big to allocate.
+    pub fn try_filled(width: usize, height: usize, value: T) -> Result<Self, AdventError> {
+        let too_big = || {
+            AdventError::Data(format!(
+                "a {0}x{1} grid is too big to allocate",
+                width, height
+            ))
+        };
+        let len = width.checked_mul(height).ok_or_else(too_big)?;
+        let mut cells = Vec::new();
+        cells.try_reserve_exact(len).map_err(|_| too_big())?;
+        cells.resize(len, value);
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &Self::Output {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!("position {0:?} out of bounds", pos),
+        }
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, pos: Position) -> &mut Self::Output {
+        match self.linear(pos) {
+            Some(idx) => &mut self.cells[idx],
+            None => panic!("position {0:?} out of bounds", pos),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    /// Write out every row on its own line, with no separators between cells.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{0}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> IntoIterator for Grid<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Grid<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.iter()
+    }
+}
+
+impl<T> From<simple_grid::Grid<T>> for Grid<T> {
+    fn from(grid: simple_grid::Grid<T>) -> Self {
+        let (width, height) = grid.dimensions();
+        Grid {
+            width,
+            height,
+            cells: grid.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> From<Grid<T>> for simple_grid::Grid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        // `simple_grid` panics if only one of the dimensions is zero.
+        if grid.is_empty() {
+            return simple_grid::Grid::new(0, 0, Vec::new());
+        }
+        simple_grid::Grid::new(grid.width, grid.height, grid.cells)
+    }
+}
+
+/// A view of a single column of a [Grid].
+#[derive(Debug)]
+pub struct Column<'a, T> {
+    grid: &'a Grid<T>,
+    x: usize,
+}
+
+// Deriving these would require `T: Clone`, which isn't needed for a reference.
+impl<T> Clone for Column<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Column<'_, T> {}
+
+impl<'a, T> Column<'a, T> {
+    /// Returns which column of the grid this is.
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    /// Returns the number of cells in the column.
+    pub fn len(&self) -> usize {
+        self.grid.height
+    }
+
+    /// Returns true if the column has no cells.
+    pub fn is_empty(&self) -> bool {
+        self.grid.height == 0
+    }
+
+    /// Get the cell in row `y`, if it exists.
+    pub fn get(&self, y: usize) -> Option<&'a T> {
+        self.grid.get((self.x, y))
+    }
+
+    /// Iterate over the cells from top to bottom.
+    pub fn iter(&self) -> std::iter::StepBy<std::slice::Iter<'a, T>> {
+        let cells = self.grid.cells.get(self.x..).unwrap_or(&[]);
+        cells.iter().step_by(self.grid.width)
+    }
+}
+
+impl<'a, T> Index<usize> for Column<'a, T> {
+    type Output = T;
+
+    fn index(&self, y: usize) -> &Self::Output {
+        &self.grid[(self.x, y)]
+    }
+}
+
+impl<'a, T> IntoIterator for Column<'a, T> {
+    type Item = &'a T;
+    type IntoIter = std::iter::StepBy<std::slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse("abc\ndef\n", Ok).expect("sample grid should parse")
+    }
+
+    #[test]
+    fn construction() {
+        assert!(Grid::new(2, 2, vec![1, 2, 3]).is_err());
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+
+        let grid = Grid::from_rows(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(grid, Grid::new(2, 2, vec![1, 2, 3, 4]).unwrap());
+        assert_eq!(grid.dimensions(), (2, 2));
+
+        let empty = Grid::<u8>::from_rows(Vec::<Vec<u8>>::new()).unwrap();
+        assert!(empty.is_empty());
+
+        let filled = Grid::try_filled(2, 2, 0).unwrap();
+        assert_eq!(filled, Grid::filled(2, 2, 0));
+        assert!(Grid::try_filled(usize::MAX, 2, 0u8).is_err());
+        assert!(Grid::try_filled(usize::MAX / 2, 1, 0u64).is_err());
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = sample();
+
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.column(1).iter().collect::<String>(), "be");
+        assert_eq!(grid.column(2)[1], 'f');
+        assert_eq!(
+            grid.columns().map(|col| col.len()).collect::<Vec<_>>(),
+            vec![2, 2, 2]
+        );
+    }
+
+    #[test]
+    fn indexing() {
+        let mut grid = sample();
+
+        assert_eq!(grid[(2, 0)], 'c');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get_offset((1, 1), (-1, -1)), Some(&'a'));
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((0, 0), (0, 2)), None);
+
+        grid[(0, 1)] = 'x';
+        assert_eq!(grid.row(1), &['x', 'e', 'f']);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(sample().to_string(), "abc\ndef\n");
+    }
+
+    #[test]
+    fn simple_grid_conversion() {
+        let grid = sample();
+        let simple: simple_grid::Grid<char> = grid.clone().into();
+
+        assert_eq!(simple.get((1, 1)), Some(&'e'));
+        assert_eq!(Grid::from(simple), grid);
+    }
+}
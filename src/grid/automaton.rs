@@ -0,0 +1,260 @@
+//! Cellular automata over a [Grid].
+
+use std::collections::BTreeSet;
+use std::mem;
+
+use super::{Edge, Grid, Offset, Position};
+
+/// How the changes made during a generation are applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Update {
+    /// Every cell sees the grid as it was at the start of the generation.
+    Synchronous,
+    /// Cells are updated in row-major order, and each change is visible
+    /// to every cell updated after it.
+    ///
+    /// This gives the same result as sweeping over every cell, but cells
+    /// that can't have changed are still skipped.
+    Asynchronous,
+}
+
+/// A cellular automaton, which repeatedly applies a rule to every cell of a grid.
+///
+/// Only the cells that could have been affected by the last generation
+/// get evaluated again, so sparse activity stays cheap.
+pub struct Automaton<'a, T, F> {
+    grid: Grid<T>,
+    stencil: &'a [Offset],
+    edge: Edge<'a, T>,
+    update: Update,
+    rule: F,
+    // Cells to evaluate next generation.
+    frontier: Vec<Position>,
+    // Which cells are already in the frontier.
+    queued: Grid<bool>,
+    changes: Vec<usize>,
+}
+
+impl<'a, T, F> Automaton<'a, T, F>
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T, &[Option<&T>]) -> T,
+{
+    /// Create an automaton that updates synchronously and ignores
+    /// neighbors outside of the grid.
+    ///
+    /// The rule is given a cell and one entry per offset in `stencil`, in
+    /// the same order, and returns what the cell becomes. An entry is `None`
+    /// when that neighbor is past the edge of the grid and the edge mode
+    /// skips it.
+    pub fn new(grid: Grid<T>, stencil: &'a [Offset], rule: F) -> Self {
+        let (width, height) = grid.dimensions();
+        Automaton {
+            frontier: grid.positions().collect(),
+            queued: Grid::filled(width, height, true),
+            grid,
+            stencil,
+            edge: Edge::Bounded,
+            update: Update::Synchronous,
+            rule,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Change how neighbors past the edge of the grid are handled.
+    pub fn with_edge(mut self, edge: Edge<'a, T>) -> Self {
+        self.edge = edge;
+        self
+    }
+
+    /// Change how updates are applied.
+    pub fn with_update(mut self, update: Update) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Run a single generation, returning how many cells changed.
+    pub fn step(&mut self) -> usize {
+        // Cells to evaluate this generation, keyed by `(y, x)` so they come out in row-major order.
+        let mut current = mem::take(&mut self.frontier)
+            .into_iter()
+            .map(|(x, y)| (y, x))
+            .collect::<BTreeSet<_>>();
+        let mut changed = Vec::new();
+        let mut pending = Vec::new();
+        while let Some((y, x)) = current.pop_first() {
+            let pos = (x, y);
+            self.queued[pos] = false;
+            let neighbors = self
+                .stencil
+                .iter()
+                .map(|&delta| match self.edge {
+                    Edge::Bounded => self.grid.offset(pos, delta).map(|pos| &self.grid[pos]),
+                    Edge::Wrapping => Some(&self.grid[self.grid.wrapping_offset(pos, delta)]),
+                    Edge::Padded(pad) => Some(match self.grid.offset(pos, delta) {
+                        Some(pos) => &self.grid[pos],
+                        None => pad,
+                    }),
+                })
+                .collect::<Vec<_>>();
+            let next = (self.rule)(&self.grid[pos], &neighbors);
+            if next == self.grid[pos] {
+                continue;
+            }
+            changed.push(pos);
+            match self.update {
+                Update::Synchronous => pending.push((pos, next)),
+                Update::Asynchronous => {
+                    self.grid[pos] = next;
+                    // Cells later in the sweep see the change this generation.
+                    let later = self.dependents(pos).map(|(x, y)| (y, x));
+                    current.extend(later.filter(|&key| key > (y, x)));
+                }
+            }
+        }
+        for (pos, next) in pending {
+            self.grid[pos] = next;
+        }
+
+        // Anything that has a changed cell in its neighborhood
+        // needs another look, as do the changed cells themselves.
+        for &pos in &changed {
+            self.schedule(pos);
+            for source in self.dependents(pos).collect::<Vec<_>>() {
+                self.schedule(source);
+            }
+        }
+
+        self.changes.push(changed.len());
+        changed.len()
+    }
+
+    /// Run a fixed number of generations, returning how many
+    /// cells changed in each of them.
+    pub fn run(&mut self, steps: usize) -> &[usize] {
+        let start = self.changes.len();
+        for _ in 0..steps {
+            self.step();
+        }
+        &self.changes[start..]
+    }
+
+    /// Run until a generation doesn't change anything, returning
+    /// how many generations that took (including the last one).
+    ///
+    /// This never returns if the automaton settles into a cycle.
+    pub fn run_to_fixed_point(&mut self) -> usize {
+        let start = self.changes.len();
+        while self.step() != 0 {}
+        self.changes.len() - start
+    }
+
+    /// Returns the cells that have `pos` in their neighborhood.
+    fn dependents(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        self.stencil
+            .iter()
+            .filter_map(move |&(dx, dy)| match self.edge {
+                Edge::Wrapping => Some(self.grid.wrapping_offset(pos, (-dx, -dy))),
+                Edge::Bounded | Edge::Padded(_) => self.grid.offset(pos, (-dx, -dy)),
+            })
+    }
+
+    fn schedule(&mut self, pos: Position) {
+        if !self.queued[pos] {
+            self.queued[pos] = true;
+            self.frontier.push(pos);
+        }
+    }
+}
+
+impl<T, F> Automaton<'_, T, F> {
+    /// Returns the grid as it currently stands.
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    /// Consume the automaton and return its grid.
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    /// Returns how many generations have been run so far.
+    pub fn generation(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns how many cells changed in each generation so far.
+    pub fn changes(&self) -> &[usize] {
+        &self.changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::grid::ADJACENT;
+
+    fn life(cell: &bool, neighbors: &[Option<&bool>]) -> bool {
+        let alive = neighbors.iter().flatten().filter(|&&&cell| cell).count();
+        matches!((cell, alive), (true, 2) | (_, 3))
+    }
+
+    fn spread(cell: &bool, neighbors: &[Option<&bool>]) -> bool {
+        *cell || neighbors[0] == Some(&true)
+    }
+
+    #[test]
+    fn blinker() {
+        let grid = Grid::parse(".....\n..#..\n..#..\n..#..\n.....\n", |ch| Ok(ch == '#')).unwrap();
+        let mut automaton = Automaton::new(grid.clone(), &ADJACENT, life);
+
+        assert_eq!(automaton.run(2), &[4, 4]);
+        assert_eq!(automaton.grid(), &grid);
+        assert_eq!(automaton.generation(), 2);
+    }
+
+    #[test]
+    fn update_modes() {
+        let grid = Grid::parse("#....\n", |ch| Ok(ch == '#')).unwrap();
+
+        let mut sync = Automaton::new(grid.clone(), &[(-1, 0)], spread);
+        assert_eq!(sync.run_to_fixed_point(), 5);
+        assert_eq!(sync.changes(), &[1, 1, 1, 1, 0]);
+
+        let mut serial = Automaton::new(grid, &[(-1, 0)], spread).with_update(Update::Asynchronous);
+        assert_eq!(serial.run_to_fixed_point(), 2);
+        assert_eq!(serial.changes(), &[4, 0]);
+        assert_eq!(serial.into_grid(), sync.into_grid());
+
+        // A change reaches cells later in the sweep that weren't due for
+        // another look, just like sweeping over every cell would.
+        let grid = Grid::parse("##...\n", |ch| Ok(ch == '#')).unwrap();
+        let toggle =
+            |cell: &bool, neighbors: &[Option<&bool>]| *cell ^ (neighbors[0] == Some(&true));
+        let mut sweep = Automaton::new(grid, &[(-1, 0)], toggle).with_update(Update::Asynchronous);
+        assert_eq!(sweep.run(2), &[1, 4]);
+        assert_eq!(sweep.grid().row(0), &[true; 5]);
+    }
+
+    #[test]
+    fn edges() {
+        let grid = Grid::parse("....#\n", |ch| Ok(ch == '#')).unwrap();
+
+        let mut wrapping =
+            Automaton::new(grid.clone(), &[(-1, 0)], spread).with_edge(Edge::Wrapping);
+        wrapping.run_to_fixed_point();
+        assert!(wrapping.grid().iter().all(|&cell| cell));
+
+        // Nothing comes in from past the edge, and the entry for it stays in place.
+        let mut bounded = Automaton::new(grid.clone(), &[(0, 0), (-1, 0)], |_, neighbors| {
+            *neighbors[0].expect("a cell is its own neighbor") || neighbors[1] == Some(&true)
+        });
+        bounded.run_to_fixed_point();
+        assert_eq!(bounded.grid().row(0), &[false, false, false, false, true]);
+
+        let mut padded = Automaton::new(grid, &[(-1, 0)], spread).with_edge(Edge::Padded(&true));
+        assert_eq!(padded.step(), 1);
+        assert_eq!(padded.grid().row(0), &[true, false, false, false, true]);
+    }
+}
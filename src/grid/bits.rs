@@ -0,0 +1,409 @@
+//! A grid of booleans packed into bits.
+
+use std::ops::{BitAnd, BitOr, BitXor};
+
+use super::{Grid, Offset, Position, ADJACENT};
+
+const BITS: usize = u64::BITS as usize;
+
+/// A grid of booleans, stored as one bit per cell.
+///
+/// Each row is packed into `u64` words with the leftmost cell in the
+/// lowest bit, so whole rows can be shifted and combined at once.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    // Invariant: bits past `width` in the last word of a row are always zero.
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// Create a grid where every cell is unset.
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(BITS);
+        BitGrid {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `(width, height)`.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns whether the cell at a position is set.
+    ///
+    /// Positions outside of the grid are never set.
+    pub fn get(&self, (x, y): Position) -> bool {
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+        self.words[y * self.stride + x / BITS] >> (x % BITS) & 1 == 1
+    }
+
+    /// Set or clear the cell at a position.
+    ///
+    /// # Panics
+    /// Panics if the position is outside of the grid.
+    pub fn set(&mut self, (x, y): Position, value: bool) {
+        assert!(
+            x < self.width && y < self.height,
+            "position {0:?} out of bounds",
+            (x, y)
+        );
+        let word = &mut self.words[y * self.stride + x / BITS];
+        let bit = 1 << (x % BITS);
+        if value {
+            *word |= bit;
+        } else {
+            *word &= !bit;
+        }
+    }
+
+    /// Returns the packed words of a single row.
+    pub fn row_words(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    /// Returns how many cells are set.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns true if any cell is set.
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&word| word != 0)
+    }
+
+    /// Iterate over the positions of every set cell, in row-major order.
+    pub fn ones(&self) -> impl Iterator<Item = Position> + '_ {
+        (0..self.height).flat_map(move |y| {
+            self.row_words(y)
+                .iter()
+                .enumerate()
+                .flat_map(move |(idx, &word)| {
+                    let mut word = word;
+                    std::iter::from_fn(move || {
+                        if word == 0 {
+                            return None;
+                        }
+                        let bit = word.trailing_zeros() as usize;
+                        word &= word - 1;
+                        Some((idx * BITS + bit, y))
+                    })
+                })
+        })
+    }
+
+    /// Returns the cells that are set in `self` but not in `other`.
+    pub fn and_not(&self, other: &BitGrid) -> BitGrid {
+        self.zip(other, |a, b| a & !b)
+    }
+
+    /// Returns the opposite of every cell.
+    pub fn not(&self) -> BitGrid {
+        let mut result = self.clone();
+        result.words.iter_mut().for_each(|word| *word = !*word);
+        result.clear_padding();
+        result
+    }
+
+    /// Shift the whole grid so that every cell takes the value
+    /// of the cell at `(x + dx, y + dy)`.
+    ///
+    /// Anything shifted in from outside of the grid is unset.
+    pub fn shifted(&self, (dx, dy): Offset) -> BitGrid {
+        let mut result = BitGrid::new(self.width, self.height);
+        let word_shift = dx.div_euclid(BITS as isize);
+        let bit_shift = dx.rem_euclid(BITS as isize) as u32;
+        for y in 0..self.height {
+            let Some(source) = y.checked_add_signed(dy).filter(|&y| y < self.height) else {
+                continue;
+            };
+            let source = self.row_words(source);
+            let word = |idx: isize| {
+                usize::try_from(idx)
+                    .ok()
+                    .and_then(|idx| source.get(idx))
+                    .copied()
+                    .unwrap_or(0)
+            };
+            let row = &mut result.words[y * self.stride..(y + 1) * self.stride];
+            for (idx, out) in row.iter_mut().enumerate() {
+                let idx = idx as isize + word_shift;
+                *out = match bit_shift {
+                    0 => word(idx),
+                    bits => (word(idx) >> bits) | (word(idx + 1) << (BITS as u32 - bits)),
+                };
+            }
+        }
+        result.clear_padding();
+        result
+    }
+
+    /// Count how many of the eight surrounding cells are set, for every cell at once.
+    pub fn neighbor_counts(&self) -> NeighborCounts {
+        self.stencil_counts(&ADJACENT)
+    }
+
+    /// Count how many cells of a stencil are set, for every cell at once.
+    ///
+    /// # Panics
+    /// Panics if the stencil has more than fifteen offsets, since
+    /// the counts wouldn't fit.
+    pub fn stencil_counts(&self, stencil: &[Offset]) -> NeighborCounts {
+        assert!(stencil.len() < 16, "stencil too large to count");
+        let mut planes: [BitGrid; 4] =
+            std::array::from_fn(|_| BitGrid::new(self.width, self.height));
+        for &offset in stencil {
+            let shifted = self.shifted(offset);
+            // Add one bit to every counter at once, rippling the carry
+            // up through the planes like a binary adder.
+            for (idx, &word) in shifted.words.iter().enumerate() {
+                let mut carry = word;
+                for plane in planes.iter_mut() {
+                    let sum = plane.words[idx] ^ carry;
+                    carry &= plane.words[idx];
+                    plane.words[idx] = sum;
+                }
+            }
+        }
+        NeighborCounts { planes }
+    }
+
+    fn zip<F: Fn(u64, u64) -> u64>(&self, other: &BitGrid, op: F) -> BitGrid {
+        assert_eq!(
+            self.dimensions(),
+            other.dimensions(),
+            "grids must be the same size"
+        );
+        let words = self
+            .words
+            .iter()
+            .zip(&other.words)
+            .map(|(&a, &b)| op(a, b))
+            .collect();
+        BitGrid {
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+            words,
+        }
+    }
+
+    /// Restore the invariant that bits past the width are zero.
+    fn clear_padding(&mut self) {
+        let used = self.width % BITS;
+        if used == 0 {
+            return;
+        }
+        let mask = (1 << used) - 1;
+        for row in self.words.chunks_mut(self.stride) {
+            if let Some(last) = row.last_mut() {
+                *last &= mask;
+            }
+        }
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, other: Self) -> BitGrid {
+        self.zip(other, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, other: Self) -> BitGrid {
+        self.zip(other, |a, b| a | b)
+    }
+}
+
+impl BitXor for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitxor(self, other: Self) -> BitGrid {
+        self.zip(other, |a, b| a ^ b)
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        let mut bits = BitGrid::new(grid.width(), grid.height());
+        for (pos, &cell) in grid.enumerate() {
+            if cell {
+                bits.set(pos, true);
+            }
+        }
+        bits
+    }
+}
+
+impl From<&BitGrid> for Grid<bool> {
+    fn from(bits: &BitGrid) -> Self {
+        let mut grid = Grid::filled(bits.width, bits.height, false);
+        for pos in bits.ones() {
+            grid[pos] = true;
+        }
+        grid
+    }
+}
+
+/// The neighbor count of every cell in a [BitGrid], stored as bit planes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NeighborCounts {
+    // Plane `n` holds bit `n` of every count.
+    planes: [BitGrid; 4],
+}
+
+impl NeighborCounts {
+    /// Returns the count for a single cell.
+    pub fn get(&self, pos: Position) -> u8 {
+        self.planes
+            .iter()
+            .enumerate()
+            .map(|(bit, plane)| (plane.get(pos) as u8) << bit)
+            .sum()
+    }
+
+    /// Returns the cells whose count is less than `limit`.
+    pub fn less_than(&self, limit: u8) -> BitGrid {
+        let (width, height) = self.planes[0].dimensions();
+        let mut less = BitGrid::new(width, height);
+        let mut equal = less.not();
+        // Every count fits in four bits, so they're all below this.
+        if limit >= 16 {
+            return equal;
+        }
+        // Compare from the highest bit down, like comparing two numbers digit by digit.
+        for (bit, plane) in self.planes.iter().enumerate().rev() {
+            if limit >> bit & 1 == 1 {
+                less = &less | &equal.and_not(plane);
+                equal = &equal & plane;
+            } else {
+                equal = equal.and_not(plane);
+            }
+        }
+        less
+    }
+
+    /// Returns the cells whose count is at least `limit`.
+    pub fn at_least(&self, limit: u8) -> BitGrid {
+        self.less_than(limit).not()
+    }
+}
+
+impl From<&NeighborCounts> for Grid<u8> {
+    fn from(counts: &NeighborCounts) -> Self {
+        let (width, height) = counts.planes[0].dimensions();
+        let mut grid = Grid::filled(width, height, 0);
+        for (bit, plane) in counts.planes.iter().enumerate() {
+            for pos in plane.ones() {
+                grid[pos] |= 1 << bit;
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<bool> {
+        Grid::parse("#.#\n###\n..#\n", |ch| Ok(ch == '#')).unwrap()
+    }
+
+    #[test]
+    fn conversion() {
+        let grid = sample();
+        let bits = BitGrid::from(&grid);
+
+        assert_eq!(bits.count_ones(), 6);
+        assert!(bits.get((2, 2)));
+        assert!(!bits.get((0, 2)));
+        assert!(!bits.get((5, 5)));
+        assert_eq!(Grid::from(&bits), grid);
+    }
+
+    #[test]
+    fn shifts() {
+        let bits = BitGrid::from(&sample());
+
+        let left = bits.shifted((1, 0));
+        assert_eq!(
+            left.ones().collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1), (1, 1), (1, 2)]
+        );
+        let down = bits.shifted((0, -1));
+        assert_eq!(down.row_words(0), &[0]);
+        assert_eq!(down.row_words(1), &[0b101]);
+    }
+
+    #[test]
+    fn wide_shifts() {
+        let mut bits = BitGrid::new(130, 1);
+        bits.set((63, 0), true);
+        bits.set((64, 0), true);
+        bits.set((129, 0), true);
+
+        let right = bits.shifted((-1, 0));
+        assert_eq!(right.ones().collect::<Vec<_>>(), vec![(64, 0), (65, 0)]);
+        let left = bits.shifted((65, 0));
+        assert_eq!(left.ones().collect::<Vec<_>>(), vec![(64, 0)]);
+        assert_eq!(bits.not().count_ones(), 127);
+    }
+
+    #[test]
+    fn counts() {
+        let grid = sample();
+        let bits = BitGrid::from(&grid);
+        let counts = bits.neighbor_counts();
+
+        for (pos, _) in grid.enumerate() {
+            let expected = grid
+                .neighbors(pos, &ADJACENT)
+                .filter(|&(_, &cell)| cell)
+                .count();
+            assert_eq!(counts.get(pos) as usize, expected, "count at {0:?}", pos);
+        }
+        assert_eq!(Grid::from(&counts)[(1, 1)], 5);
+        assert_eq!(
+            counts.less_than(3).ones().collect::<Vec<_>>(),
+            vec![(0, 0), (2, 0), (0, 1), (0, 2), (2, 2)]
+        );
+        assert_eq!(counts.at_least(3).count_ones(), 4);
+    }
+
+    #[test]
+    fn combining() {
+        let bits = BitGrid::from(&sample());
+        let shifted = bits.shifted((1, 0));
+
+        assert_eq!((&bits & &shifted).count_ones(), 2);
+        assert_eq!((&bits | &shifted).count_ones(), 8);
+        assert_eq!((&bits ^ &shifted).count_ones(), 6);
+        assert_eq!(bits.and_not(&shifted).count_ones(), 4);
+    }
+}
@@ -0,0 +1,238 @@
+//! Neighborhoods of cells in a [Grid].
+
+use super::{Grid, Position};
+
+/// A signed offset between two positions, given as `(dx, dy)`.
+///
+/// Positive `dx` goes right and positive `dy` goes down.
+pub type Offset = (isize, isize);
+
+/// The four cells sharing an edge with a cell, clockwise from the top.
+pub const CARDINAL: [Offset; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// The eight cells sharing an edge or a corner with a cell,
+/// clockwise from the top.
+pub const ADJACENT: [Offset; 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// One of the eight compass directions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The four directions that don't move diagonally, clockwise from north.
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// Returns the offset of one step in this direction.
+    ///
+    /// North is up, which is towards row zero.
+    pub fn offset(self) -> Offset {
+        ADJACENT[self as usize]
+    }
+
+    /// Returns the direction pointing the other way.
+    pub fn opposite(self) -> Direction {
+        Direction::ALL[(self as usize + 4) % 8]
+    }
+
+    /// Returns the direction a quarter turn clockwise.
+    pub fn clockwise(self) -> Direction {
+        Direction::ALL[(self as usize + 2) % 8]
+    }
+
+    /// Returns the direction a quarter turn counterclockwise.
+    pub fn counterclockwise(self) -> Direction {
+        Direction::ALL[(self as usize + 6) % 8]
+    }
+}
+
+/// What to do with neighbors that fall off of the edge of the grid.
+#[derive(Debug)]
+pub enum Edge<'a, T> {
+    /// Skip them entirely.
+    Bounded,
+    /// Wrap around to the other side, as if the grid were a torus.
+    Wrapping,
+    /// Pretend the grid is surrounded by infinitely many copies of a cell.
+    Padded(&'a T),
+}
+
+// Deriving these would require `T: Clone`, which isn't needed for a reference.
+impl<T> Clone for Edge<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Edge<'_, T> {}
+
+impl<T> Grid<T> {
+    /// Move a position by a signed offset, wrapping around the edges.
+    ///
+    /// # Panics
+    /// Panics if the grid is empty.
+    pub fn wrapping_offset(&self, (x, y): Position, (dx, dy): Offset) -> Position {
+        assert!(!self.is_empty(), "cannot wrap around an empty grid");
+        let wrap = |pos: usize, delta: isize, len: usize| {
+            let len = len as isize;
+            (pos as isize % len + delta % len).rem_euclid(len) as usize
+        };
+        (wrap(x, dx, self.width), wrap(y, dy, self.height))
+    }
+
+    /// Iterate over the neighbors of a position that lie inside of the grid.
+    ///
+    /// `stencil` picks which offsets count as neighbors, usually
+    /// [CARDINAL] or [ADJACENT].
+    pub fn neighbors<'a>(
+        &'a self,
+        pos: Position,
+        stencil: &'a [Offset],
+    ) -> impl Iterator<Item = (Position, &'a T)> + 'a {
+        stencil.iter().filter_map(move |&delta| {
+            let pos = self.offset(pos, delta)?;
+            Some((pos, &self[pos]))
+        })
+    }
+
+    /// Iterate over the neighbors of a position, wrapping around the edges.
+    pub fn wrapping_neighbors<'a>(
+        &'a self,
+        pos: Position,
+        stencil: &'a [Offset],
+    ) -> impl Iterator<Item = (Position, &'a T)> + 'a {
+        // An empty grid has no neighbors to wrap around to.
+        let stencil = if self.is_empty() { &[] } else { stencil };
+        stencil.iter().map(move |&delta| {
+            let pos = self.wrapping_offset(pos, delta);
+            (pos, &self[pos])
+        })
+    }
+
+    /// Iterate over the neighbors of a position, handling the edges as asked.
+    ///
+    /// Neighbors from [Edge::Padded] have no position, since they
+    /// aren't actually part of the grid.
+    pub fn neighborhood<'a>(
+        &'a self,
+        pos: Position,
+        stencil: &'a [Offset],
+        edge: Edge<'a, T>,
+    ) -> impl Iterator<Item = (Option<Position>, &'a T)> + 'a {
+        stencil.iter().filter_map(move |&delta| match edge {
+            Edge::Bounded => self.offset(pos, delta).map(|pos| (Some(pos), &self[pos])),
+            Edge::Wrapping if self.is_empty() => None,
+            Edge::Wrapping => {
+                let pos = self.wrapping_offset(pos, delta);
+                Some((Some(pos), &self[pos]))
+            }
+            Edge::Padded(pad) => match self.offset(pos, delta) {
+                Some(pos) => Some((Some(pos), &self[pos])),
+                None => Some((None, pad)),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<u8> {
+        Grid::new(3, 3, (1..=9).collect()).unwrap()
+    }
+
+    #[test]
+    fn directions() {
+        assert_eq!(Direction::North.offset(), (0, -1));
+        assert_eq!(Direction::SouthWest.offset(), (-1, 1));
+        assert_eq!(Direction::East.opposite(), Direction::West);
+        assert_eq!(Direction::North.clockwise(), Direction::East);
+        assert_eq!(Direction::North.counterclockwise(), Direction::West);
+        assert_eq!(
+            Direction::CARDINAL.map(Direction::offset),
+            CARDINAL,
+            "cardinal directions should match stencil"
+        );
+    }
+
+    #[test]
+    fn bounded() {
+        let grid = sample();
+
+        let corner = grid.neighbors((0, 0), &ADJACENT).collect::<Vec<_>>();
+        assert_eq!(corner, vec![((1, 0), &2), ((1, 1), &5), ((0, 1), &4)]);
+        assert_eq!(grid.neighbors((1, 1), &ADJACENT).count(), 8);
+        assert_eq!(grid.neighbors((1, 1), &CARDINAL).count(), 4);
+        assert_eq!(grid.neighbors((1, 1), &[(2, 2)]).count(), 0);
+    }
+
+    #[test]
+    fn wrapping() {
+        let grid = sample();
+
+        assert_eq!(grid.wrapping_offset((0, 0), (-1, -1)), (2, 2));
+        assert_eq!(grid.wrapping_offset((2, 1), (7, -5)), (0, 2));
+        let cells = grid
+            .wrapping_neighbors((0, 0), &CARDINAL)
+            .map(|(_, &cell)| cell)
+            .collect::<Vec<_>>();
+        assert_eq!(cells, vec![7, 2, 4, 3]);
+    }
+
+    #[test]
+    fn padded() {
+        let grid = sample();
+
+        let cells = grid
+            .neighborhood((0, 0), &CARDINAL, Edge::Padded(&0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cells,
+            vec![
+                (None, &0),
+                (Some((1, 0)), &2),
+                (Some((0, 1)), &4),
+                (None, &0)
+            ]
+        );
+        assert_eq!(
+            grid.neighborhood((0, 0), &ADJACENT, Edge::Bounded).count(),
+            3
+        );
+    }
+}
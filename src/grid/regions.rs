@@ -0,0 +1,236 @@
+//! Splitting a [Grid] up into connected regions.
+//!
+//! Connectivity is given as a stencil, so [CARDINAL] only joins cells
+//! that share an edge and [ADJACENT](super::ADJACENT) joins corners too.
+
+use std::collections::VecDeque;
+
+use super::{Grid, Offset, Position, CARDINAL};
+
+/// A summary of one connected region of a [Grid].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Region {
+    /// How many cells are in the region.
+    pub size: usize,
+    /// The top left corner of the smallest box around the region.
+    pub min: Position,
+    /// The bottom right corner of that box, inclusive.
+    pub max: Position,
+    /// How many cell edges separate the region from everything else,
+    /// including the outside of the grid.
+    pub perimeter: usize,
+}
+
+/// Every region of a [Grid], and which region each cell belongs to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Regions {
+    labels: Grid<usize>,
+    regions: Vec<Region>,
+}
+
+impl Regions {
+    /// Returns the label of the region containing a position.
+    ///
+    /// Labels count up from zero in the order the regions are first
+    /// seen, reading the grid in row-major order.
+    pub fn label(&self, pos: Position) -> Option<usize> {
+        self.labels.get(pos).copied()
+    }
+
+    /// Returns the label of every cell.
+    pub fn labels(&self) -> &Grid<usize> {
+        &self.labels
+    }
+
+    /// Returns the region with a label.
+    pub fn get(&self, label: usize) -> Option<&Region> {
+        self.regions.get(label)
+    }
+
+    /// Returns the region containing a position.
+    pub fn region_at(&self, pos: Position) -> Option<&Region> {
+        self.get(self.label(pos)?)
+    }
+
+    /// Returns how many regions there are.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns true if there are no regions, which only happens for an empty grid.
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Iterate over every region, in label order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Region> {
+        self.regions.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Regions {
+    type Item = &'a Region;
+    type IntoIter = std::slice::Iter<'a, Region>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Grid<T> {
+    /// Find every cell connected to `seed`.
+    ///
+    /// Two neighboring cells are connected if `same` returns true for them.
+    /// The cells are returned in row-major order, and include the seed
+    /// itself unless it's outside of the grid.
+    pub fn flood_fill<F>(&self, seed: Position, stencil: &[Offset], mut same: F) -> Vec<Position>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        if !self.contains(seed) {
+            return Vec::new();
+        }
+        let mut seen = Grid::filled(self.width, self.height, false);
+        let mut filled = self.fill(seed, stencil, &mut same, &mut seen);
+        filled.sort_unstable_by_key(|&(x, y)| (y, x));
+        filled
+    }
+
+    /// Label every connected region of the grid.
+    ///
+    /// Two neighboring cells are in the same region if `same` returns true
+    /// for them. `same` should be symmetric, or the regions found will
+    /// depend on which cell each search happens to start from.
+    pub fn regions<F>(&self, stencil: &[Offset], mut same: F) -> Regions
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut seen = Grid::filled(self.width, self.height, false);
+        let mut labels = Grid::filled(self.width, self.height, 0);
+        let mut regions = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if seen[(x, y)] {
+                    continue;
+                }
+                let label = regions.len();
+                let cells = self.fill((x, y), stencil, &mut same, &mut seen);
+                for &pos in &cells {
+                    labels[pos] = label;
+                }
+                regions.push(Region {
+                    size: cells.len(),
+                    min: (x, y),
+                    max: (x, y),
+                    perimeter: 0,
+                });
+                // The bounding box and perimeter need every cell labeled first.
+                let region = &mut regions[label];
+                for &(x, y) in &cells {
+                    region.min = (region.min.0.min(x), region.min.1.min(y));
+                    region.max = (region.max.0.max(x), region.max.1.max(y));
+                    region.perimeter += CARDINAL
+                        .iter()
+                        .filter(|&&offset| {
+                            self.offset((x, y), offset)
+                                .is_none_or(|next| labels[next] != label || !seen[next])
+                        })
+                        .count();
+                }
+            }
+        }
+        Regions { labels, regions }
+    }
+
+    /// Breadth-first search out from `seed`, marking everything found in `seen`.
+    fn fill<F>(
+        &self,
+        seed: Position,
+        stencil: &[Offset],
+        same: &mut F,
+        seen: &mut Grid<bool>,
+    ) -> Vec<Position>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut cells = vec![seed];
+        let mut queue = VecDeque::from([seed]);
+        seen[seed] = true;
+        while let Some(pos) = queue.pop_front() {
+            for (next, cell) in self.neighbors(pos, stencil) {
+                if !seen[next] && same(&self[pos], cell) {
+                    seen[next] = true;
+                    cells.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::grid::ADJACENT;
+
+    fn garden() -> Grid<char> {
+        Grid::parse("AAAA\nBBCD\nBBCC\nEEEC\n", Ok).unwrap()
+    }
+
+    #[test]
+    fn flood_fill() {
+        let grid = garden();
+
+        assert_eq!(
+            grid.flood_fill((2, 1), &CARDINAL, |a, b| a == b),
+            vec![(2, 1), (2, 2), (3, 2), (3, 3)]
+        );
+        assert_eq!(grid.flood_fill((3, 1), &CARDINAL, |a, b| a == b).len(), 1);
+        assert!(grid.flood_fill((4, 0), &CARDINAL, |a, b| a == b).is_empty());
+    }
+
+    #[test]
+    fn labels() {
+        let grid = garden();
+        let regions = grid.regions(&CARDINAL, |a, b| a == b);
+
+        assert_eq!(regions.len(), 5);
+        assert_eq!(regions.label((0, 0)), Some(0));
+        assert_eq!(regions.label((1, 2)), Some(1));
+        assert_eq!(regions.label((3, 3)), Some(2));
+        assert_eq!(
+            regions.iter().map(|region| region.size).collect::<Vec<_>>(),
+            vec![4, 4, 4, 1, 3]
+        );
+        assert_eq!(
+            regions
+                .iter()
+                .map(|region| region.perimeter)
+                .collect::<Vec<_>>(),
+            vec![10, 8, 10, 4, 8]
+        );
+        assert_eq!(
+            regions.region_at((2, 1)),
+            Some(&Region {
+                size: 4,
+                min: (2, 1),
+                max: (3, 3),
+                perimeter: 10
+            })
+        );
+    }
+
+    #[test]
+    fn diagonals() {
+        let grid = Grid::parse("#..\n.#.\n..#\n", Ok).unwrap();
+
+        let straight = grid.regions(&CARDINAL, |a, b| a == b);
+        assert_eq!(straight.len(), 5);
+        let diagonal = grid.regions(&ADJACENT, |a, b| a == b);
+        assert_eq!(diagonal.len(), 2);
+        assert_eq!(diagonal.get(0).map(|region| region.perimeter), Some(12));
+        assert_eq!(diagonal.get(0).map(|region| region.max), Some((2, 2)));
+    }
+}
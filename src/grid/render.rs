@@ -0,0 +1,306 @@
+//! Turning a [Grid] into something a person can look at.
+//!
+//! Everything here writes to a `String` or an [io::Write], so it works
+//! just as well in a test as it does in a terminal.
+
+use std::io;
+
+use super::Grid;
+use crate::AdventError;
+
+/// A 24-bit RGB color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::new(0, 0, 0);
+    pub const WHITE: Color = Color::new(255, 255, 255);
+    pub const GRAY: Color = Color::new(128, 128, 128);
+    pub const RED: Color = Color::new(255, 0, 0);
+    pub const GREEN: Color = Color::new(0, 255, 0);
+    pub const BLUE: Color = Color::new(0, 0, 255);
+    pub const YELLOW: Color = Color::new(255, 255, 0);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Draw the grid as text, picking a character for every cell.
+    pub fn render<F: FnMut(&T) -> char>(&self, mut draw: F) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            out.extend(row.iter().map(&mut draw));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Draw the grid as text colored with ANSI escape codes.
+    ///
+    /// Every cell gets a character and a foreground color. The colors
+    /// are reset at the end of each line.
+    pub fn render_ansi<F: FnMut(&T) -> (char, Color)>(&self, mut draw: F) -> String {
+        let mut out = String::new();
+        for row in self.rows() {
+            let mut current = None;
+            for cell in row {
+                let (ch, color) = draw(cell);
+                // Only emit an escape code when the color actually changes.
+                if current != Some(color) {
+                    out.push_str(&format!(
+                        "\x1b[38;2;{0};{1};{2}m",
+                        color.r, color.g, color.b
+                    ));
+                    current = Some(color);
+                }
+                out.push(ch);
+            }
+            out.push_str("\x1b[0m\n");
+        }
+        out
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Blow the grid up so that every cell becomes a `factor` by `factor` block.
+    ///
+    /// Panics if the bigger grid has more cells than fit in a `usize`.
+    pub fn upscale(&self, factor: usize) -> Grid<T> {
+        let (Some(width), Some(height), Some(len)) = (
+            self.width.checked_mul(factor),
+            self.height.checked_mul(factor),
+            self.cells
+                .len()
+                .checked_mul(factor)
+                .and_then(|len| len.checked_mul(factor)),
+        ) else {
+            panic!(
+                "a {0}x{1} grid has too many cells to upscale by {2}",
+                self.width, self.height, factor
+            );
+        };
+        let mut cells = Vec::with_capacity(len);
+        for row in self.rows() {
+            for _ in 0..factor {
+                for cell in row {
+                    cells.extend(std::iter::repeat_n(cell, factor).cloned());
+                }
+            }
+        }
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+/// A grid of colors can be saved as an image, with one pixel per cell.
+///
+/// Use [Grid::map] to pick a color for every cell first, and
+/// [Grid::upscale] if the pixels are too small to see.
+impl Grid<Color> {
+    /// Write the grid out as a binary PPM (`P6`) image.
+    pub fn write_ppm<W: io::Write>(&self, mut out: W) -> Result<(), AdventError> {
+        write!(out, "P6\n{0} {1}\n255\n", self.width, self.height)?;
+        out.write_all(&self.rgb_bytes())?;
+        Ok(())
+    }
+
+    /// Write the grid out as an RGB PNG image.
+    ///
+    /// The image data is stored without compression, which keeps the
+    /// encoder small at the cost of larger files.
+    pub fn write_png<W: io::Write>(&self, mut out: W) -> Result<(), AdventError> {
+        if self.is_empty() {
+            return Err(AdventError::Data(
+                "PNG images must have at least one pixel".to_string(),
+            ));
+        }
+        let width = u32::try_from(self.width)
+            .map_err(|_| AdventError::Data(format!("image too wide: {0}", self.width)))?;
+        let height = u32::try_from(self.height)
+            .map_err(|_| AdventError::Data(format!("image too tall: {0}", self.height)))?;
+
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend(width.to_be_bytes());
+        header.extend(height.to_be_bytes());
+        // Bit depth 8, color type 2 (RGB), default compression,
+        // filtering and no interlacing.
+        header.extend([8, 2, 0, 0, 0]);
+        write_chunk(&mut out, b"IHDR", &header)?;
+
+        // Every scanline starts with its filter type, which is always zero (none).
+        let mut scanlines = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.rows() {
+            scanlines.push(0);
+            scanlines.extend(row.iter().flat_map(|color| [color.r, color.g, color.b]));
+        }
+        write_chunk(&mut out, b"IDAT", &zlib_stored(&scanlines))?;
+        write_chunk(&mut out, b"IEND", &[])?;
+        Ok(())
+    }
+
+    fn rgb_bytes(&self) -> Vec<u8> {
+        self.cells
+            .iter()
+            .flat_map(|color| [color.r, color.g, color.b])
+            .collect()
+    }
+}
+
+/// Write a single PNG chunk: length, type, data and checksum.
+fn write_chunk<W: io::Write>(out: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(data.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "PNG chunk too large"))?;
+    out.write_all(&len.to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+/// Wrap data in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // Stored blocks can hold at most this many bytes each.
+    const BLOCK: usize = u16::MAX as usize;
+
+    // Deflate with a 32K window, no preset dictionary, fastest compression.
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(BLOCK).peekable();
+    if blocks.peek().is_none() {
+        // Even no data needs a single (empty) final block.
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+/// The CRC-32 used by PNG chunks.
+fn crc32<'a, I: IntoIterator<Item = &'a u8>>(data: I) -> u32 {
+    let crc = data.into_iter().fold(u32::MAX, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    });
+    !crc
+}
+
+/// The Adler-32 checksum used at the end of zlib streams.
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (a, b) = data.iter().fold((1, 0), |(a, b), &byte| {
+        let a = (a + byte as u32) % MOD;
+        (a, (b + a) % MOD)
+    });
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<bool> {
+        Grid::parse("#.\n.#\n", |ch| Ok(ch == '#')).unwrap()
+    }
+
+    fn color(&cell: &bool) -> Color {
+        if cell {
+            Color::WHITE
+        } else {
+            Color::BLACK
+        }
+    }
+
+    #[test]
+    fn text() {
+        let grid = sample();
+
+        assert_eq!(
+            grid.render(|&cell| if cell { '#' } else { ' ' }),
+            "# \n #\n"
+        );
+        assert_eq!(
+            grid.render_ansi(|&cell| ('x', color(&cell))),
+            "\x1b[38;2;255;255;255mx\x1b[38;2;0;0;0mx\x1b[0m\n\
+             \x1b[38;2;0;0;0mx\x1b[38;2;255;255;255mx\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn upscale() {
+        let grid = sample().upscale(2);
+
+        assert_eq!(grid.dimensions(), (4, 4));
+        assert_eq!(grid.row(1), &[true, true, false, false]);
+        assert_eq!(grid.row(2), &[false, false, true, true]);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many cells")]
+    fn upscale_overflow() {
+        sample().upscale(usize::MAX / 2);
+    }
+
+    #[test]
+    fn ppm() {
+        let mut out = Vec::new();
+        sample().map(color).write_ppm(&mut out).unwrap();
+
+        let (header, body) = out.split_at(11);
+        assert_eq!(header, b"P6\n2 2\n255\n");
+        assert_eq!(body, &[255, 255, 255, 0, 0, 0, 0, 0, 0, 255, 255, 255]);
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn png() {
+        let mut out = Vec::new();
+        sample().map(color).write_png(&mut out).unwrap();
+
+        assert_eq!(&out[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&out[12..16], b"IHDR");
+        assert_eq!(&out[16..24], &[0, 0, 0, 2, 0, 0, 0, 2]);
+        // The image ends with an empty IEND chunk.
+        assert_eq!(&out[out.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+
+        let empty = Grid::<Color>::new(0, 0, vec![]).unwrap();
+        assert!(empty.write_png(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn stored_blocks() {
+        let data = vec![7; 70_000];
+        let stream = zlib_stored(&data);
+
+        // Header, two block headers, the data, and the checksum.
+        assert_eq!(stream.len(), 2 + 5 + 5 + data.len() + 4);
+        assert_eq!(stream[2], 0);
+        assert_eq!(stream[2 + 5 + 65535], 1);
+    }
+}
@@ -0,0 +1,260 @@
+//! Finding paths through a [Grid].
+//!
+//! Moves are given as a stencil of offsets, the same way as for
+//! neighborhoods, so [CARDINAL](super::CARDINAL) allows only straight
+//! moves and [ADJACENT](super::ADJACENT) allows diagonal ones as well.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use super::{Grid, Offset, Position};
+
+/// The result of searching a grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Paths {
+    distances: Grid<Option<u64>>,
+    previous: Grid<Option<Position>>,
+    reached: Option<Position>,
+}
+
+impl Paths {
+    fn new<T>(grid: &Grid<T>) -> Self {
+        let (width, height) = grid.dimensions();
+        Paths {
+            distances: Grid::filled(width, height, None),
+            previous: Grid::filled(width, height, None),
+            reached: None,
+        }
+    }
+
+    /// Returns the distance from the nearest source to a position,
+    /// or `None` if the search never got there.
+    pub fn distance(&self, pos: Position) -> Option<u64> {
+        self.distances.get(pos).copied().flatten()
+    }
+
+    /// Returns the distance to every position in the grid.
+    pub fn distances(&self) -> &Grid<Option<u64>> {
+        &self.distances
+    }
+
+    /// Returns the position the shortest path to `pos` came from.
+    ///
+    /// Sources and unreached positions have no predecessor.
+    pub fn previous(&self, pos: Position) -> Option<Position> {
+        self.previous.get(pos).copied().flatten()
+    }
+
+    /// Returns the predecessor of every position in the grid.
+    pub fn predecessors(&self) -> &Grid<Option<Position>> {
+        &self.previous
+    }
+
+    /// Returns the first target the search reached, if any.
+    pub fn reached(&self) -> Option<Position> {
+        self.reached
+    }
+
+    /// Rebuild the shortest path to a position, starting from its source.
+    pub fn path_to(&self, pos: Position) -> Option<Vec<Position>> {
+        self.distance(pos)?;
+        let mut path = vec![pos];
+        let mut current = pos;
+        while let Some(prev) = self.previous(current) {
+            path.push(prev);
+            current = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Rebuild the shortest path to the target that was reached.
+    pub fn path(&self) -> Option<Vec<Position>> {
+        self.path_to(self.reached?)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Breadth-first search, where every move costs one.
+    ///
+    /// The search stops as soon as it reaches any of `targets`. With no
+    /// targets, it finds the distance to everything reachable.
+    pub fn bfs<I, F>(
+        &self,
+        sources: I,
+        targets: &[Position],
+        stencil: &[Offset],
+        mut passable: F,
+    ) -> Paths
+    where
+        I: IntoIterator<Item = Position>,
+        F: FnMut(&T) -> bool,
+    {
+        let mut paths = Paths::new(self);
+        let mut queue = VecDeque::new();
+        for source in sources {
+            if self.contains(source) && paths.distances[source].is_none() {
+                paths.distances[source] = Some(0);
+                queue.push_back(source);
+            }
+        }
+
+        while let Some(pos) = queue.pop_front() {
+            if targets.contains(&pos) {
+                paths.reached = Some(pos);
+                break;
+            }
+            let dist = paths.distances[pos].expect("queued positions have a distance");
+            for (next, cell) in self.neighbors(pos, stencil) {
+                if paths.distances[next].is_none() && passable(cell) {
+                    paths.distances[next] = Some(dist + 1);
+                    paths.previous[next] = Some(pos);
+                    queue.push_back(next);
+                }
+            }
+        }
+        paths
+    }
+
+    /// Dijkstra's algorithm, for when moves have different costs.
+    ///
+    /// `cost` is given the cell being moved from and the cell being moved
+    /// to, and returns `None` if the move isn't allowed.
+    pub fn dijkstra<I, F>(
+        &self,
+        sources: I,
+        targets: &[Position],
+        stencil: &[Offset],
+        cost: F,
+    ) -> Paths
+    where
+        I: IntoIterator<Item = Position>,
+        F: FnMut(&T, &T) -> Option<u64>,
+    {
+        self.astar(sources, targets, stencil, cost, |_| 0)
+    }
+
+    /// A* search, which uses a heuristic to head towards the targets first.
+    ///
+    /// The heuristic estimates the remaining cost from a position to the
+    /// nearest target. It must never overestimate, and must not drop by
+    /// more than the cost of a move, or the paths found may not be shortest.
+    pub fn astar<I, F, H>(
+        &self,
+        sources: I,
+        targets: &[Position],
+        stencil: &[Offset],
+        mut cost: F,
+        mut heuristic: H,
+    ) -> Paths
+    where
+        I: IntoIterator<Item = Position>,
+        F: FnMut(&T, &T) -> Option<u64>,
+        H: FnMut(Position) -> u64,
+    {
+        let mut paths = Paths::new(self);
+        let mut queue = BinaryHeap::new();
+        for source in sources {
+            if self.contains(source) && paths.distances[source].is_none() {
+                paths.distances[source] = Some(0);
+                queue.push(Reverse((heuristic(source), 0, source)));
+            }
+        }
+
+        while let Some(Reverse((_, dist, pos))) = queue.pop() {
+            // Skip anything we've since found a shorter way to.
+            if paths.distances[pos].is_some_and(|best| best < dist) {
+                continue;
+            }
+            if targets.contains(&pos) {
+                paths.reached = Some(pos);
+                break;
+            }
+            for (next, cell) in self.neighbors(pos, stencil) {
+                let Some(step) = cost(&self[pos], cell) else {
+                    continue;
+                };
+                let next_dist = dist + step;
+                if paths.distances[next].is_none_or(|best| next_dist < best) {
+                    paths.distances[next] = Some(next_dist);
+                    paths.previous[next] = Some(pos);
+                    queue.push(Reverse((next_dist + heuristic(next), next_dist, next)));
+                }
+            }
+        }
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::grid::{ADJACENT, CARDINAL};
+
+    fn maze() -> Grid<char> {
+        Grid::parse("S.#.\n.##.\n...E\n", Ok).unwrap()
+    }
+
+    fn manhattan((x, y): Position) -> u64 {
+        (x.abs_diff(3) + y.abs_diff(2)) as u64
+    }
+
+    #[test]
+    fn bfs() {
+        let grid = maze();
+
+        let paths = grid.bfs([(0, 0)], &[], &CARDINAL, |&cell| cell != '#');
+        assert_eq!(paths.distance((3, 2)), Some(5));
+        assert_eq!(paths.distance((3, 0)), Some(7));
+        assert_eq!(paths.distance((2, 0)), None);
+        assert_eq!(
+            paths.path_to((3, 2)),
+            Some(vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (3, 2)])
+        );
+
+        let paths = grid.bfs([(0, 0)], &[(3, 2)], &ADJACENT, |&cell| cell != '#');
+        assert_eq!(paths.reached(), Some((3, 2)));
+        assert_eq!(paths.path().map(|path| path.len()), Some(5));
+    }
+
+    #[test]
+    fn multiple_sources() {
+        let grid = maze();
+
+        let paths = grid.bfs([(0, 0), (3, 0)], &[], &CARDINAL, |&cell| cell != '#');
+        assert_eq!(paths.distance((3, 2)), Some(2));
+        assert_eq!(paths.path_to((3, 2)), Some(vec![(3, 0), (3, 1), (3, 2)]));
+        assert_eq!(paths.previous((0, 0)), None);
+    }
+
+    #[test]
+    fn weighted() {
+        let grid = Grid::parse("1191\n9191\n1111\n", |ch| {
+            Ok(ch.to_digit(10).expect("digits only") as u64)
+        })
+        .unwrap();
+        let cost = |_: &u64, &to: &u64| Some(to);
+
+        let paths = grid.dijkstra([(0, 0)], &[(3, 0)], &CARDINAL, cost);
+        assert_eq!(paths.reached(), Some((3, 0)));
+        assert_eq!(paths.distance((3, 0)), Some(7));
+
+        let astar = grid.astar([(0, 0)], &[(3, 0)], &CARDINAL, cost, |(x, y)| {
+            (x.abs_diff(3) + y) as u64
+        });
+        assert_eq!(astar.distance((3, 0)), Some(7));
+        assert_eq!(astar.path(), paths.path());
+    }
+
+    #[test]
+    fn unreachable() {
+        let grid = maze();
+        let cost = |_: &char, &to: &char| (to != '#').then_some(1);
+
+        let paths = grid.astar([(0, 0)], &[(2, 0)], &CARDINAL, cost, manhattan);
+        assert_eq!(paths.reached(), None);
+        assert_eq!(paths.path(), None);
+        assert_eq!(paths.distance((3, 2)), Some(5));
+    }
+}
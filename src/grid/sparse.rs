@@ -0,0 +1,358 @@
+//! Unbounded grids that only store the cells that are actually used.
+
+use std::collections::btree_map::{self, BTreeMap};
+
+use super::Grid;
+use crate::AdventError;
+
+/// The smallest box containing every cell of a [SparseGrid].
+///
+/// Both corners are inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds<const N: usize> {
+    pub min: [i64; N],
+    pub max: [i64; N],
+}
+
+impl<const N: usize> Bounds<N> {
+    /// Returns whether a point lies inside of the box.
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        (0..N).all(|axis| (self.min[axis]..=self.max[axis]).contains(&point[axis]))
+    }
+
+    /// Returns how many cells wide the box is along each axis.
+    ///
+    /// Returns `None` if the box is inside out along any axis, or spans
+    /// all of `i64` so that its width doesn't fit in a `u64`.
+    pub fn size(&self) -> Option<[u64; N]> {
+        let mut size = [0; N];
+        for (axis, len) in size.iter_mut().enumerate() {
+            if self.min[axis] > self.max[axis] {
+                return None;
+            }
+            *len = self.min[axis].abs_diff(self.max[axis]).checked_add(1)?;
+        }
+        Some(size)
+    }
+}
+
+/// A grid with signed coordinates that stores only the cells inserted into it.
+///
+/// Points are given as `[x, y]` in two dimensions or `[x, y, z]` in three,
+/// and iteration goes in row-major order (`x` fastest, then `y`, then `z`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T, const N: usize = 2> {
+    // Keys are stored with their axes reversed, so that
+    // the map's ordering is row-major.
+    cells: BTreeMap<[i64; N], T>,
+    // How many cells use each coordinate, per axis,
+    // which keeps the bounding box exact when cells are removed.
+    axes: [BTreeMap<i64, usize>; N],
+}
+
+/// A [SparseGrid] in three dimensions.
+pub type SparseGrid3<T> = SparseGrid<T, 3>;
+
+impl<T, const N: usize> SparseGrid<T, N> {
+    /// Create an empty grid.
+    pub fn new() -> Self {
+        SparseGrid {
+            cells: BTreeMap::new(),
+            axes: std::array::from_fn(|_| BTreeMap::new()),
+        }
+    }
+
+    /// Returns how many cells are stored.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns true if no cells are stored.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns whether a cell is stored at a point.
+    pub fn contains(&self, point: [i64; N]) -> bool {
+        self.cells.contains_key(&key(point))
+    }
+
+    /// Get a reference to the cell at a point, if there is one.
+    pub fn get(&self, point: [i64; N]) -> Option<&T> {
+        self.cells.get(&key(point))
+    }
+
+    /// Get a mutable reference to the cell at a point, if there is one.
+    pub fn get_mut(&mut self, point: [i64; N]) -> Option<&mut T> {
+        self.cells.get_mut(&key(point))
+    }
+
+    /// Store a cell at a point, returning whatever was there before.
+    pub fn insert(&mut self, point: [i64; N], value: T) -> Option<T> {
+        let old = self.cells.insert(key(point), value);
+        if old.is_none() {
+            for (axis, counts) in self.axes.iter_mut().enumerate() {
+                *counts.entry(point[axis]).or_default() += 1;
+            }
+        }
+        old
+    }
+
+    /// Remove the cell at a point, returning it if there was one.
+    pub fn remove(&mut self, point: [i64; N]) -> Option<T> {
+        let old = self.cells.remove(&key(point))?;
+        for (axis, counts) in self.axes.iter_mut().enumerate() {
+            if let btree_map::Entry::Occupied(mut entry) = counts.entry(point[axis]) {
+                *entry.get_mut() -= 1;
+                if *entry.get() == 0 {
+                    entry.remove();
+                }
+            }
+        }
+        Some(old)
+    }
+
+    /// Returns the smallest box containing every stored cell.
+    pub fn bounds(&self) -> Option<Bounds<N>> {
+        let mut min = [0; N];
+        let mut max = [0; N];
+        for (axis, counts) in self.axes.iter().enumerate() {
+            min[axis] = *counts.first_key_value()?.0;
+            max[axis] = *counts.last_key_value()?.0;
+        }
+        Some(Bounds { min, max })
+    }
+
+    /// Iterate over every stored cell in row-major order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = ([i64; N], &T)> {
+        self.cells.iter().map(|(k, v)| (key(*k), v))
+    }
+
+    /// Iterate over every stored point in row-major order.
+    pub fn points(&self) -> impl DoubleEndedIterator<Item = [i64; N]> + '_ {
+        self.cells.keys().map(|&k| key(k))
+    }
+
+    /// Iterate over the stored cells around a point.
+    ///
+    /// Offsets in the stencil without a cell are skipped, as are
+    /// offsets that would go past the edge of `i64`.
+    pub fn neighbors<'a>(
+        &'a self,
+        point: [i64; N],
+        stencil: &'a [[i64; N]],
+    ) -> impl Iterator<Item = ([i64; N], &'a T)> + 'a {
+        stencil.iter().filter_map(move |offset| {
+            let mut next = [0; N];
+            for axis in 0..N {
+                next[axis] = point[axis].checked_add(offset[axis])?;
+            }
+            self.get(next).map(|cell| (next, cell))
+        })
+    }
+}
+
+impl<T: Clone> SparseGrid<T, 2> {
+    /// Copy a rectangular window out into a dense [Grid].
+    ///
+    /// The top left corner of the window becomes `(0, 0)`, and
+    /// anything without a stored cell is filled in with `fill`.
+    ///
+    /// Fails if the window is inside out or too big to fit in memory.
+    pub fn to_grid(&self, window: Bounds<2>, fill: T) -> Result<Grid<T>, AdventError> {
+        let invalid = || AdventError::Data(format!("invalid window {0:?}", window));
+        let size = window.size().ok_or_else(invalid)?;
+        let [width, height] = size.map(usize::try_from);
+        let (Ok(width), Ok(height)) = (width, height) else {
+            return Err(invalid());
+        };
+        let mut grid = Grid::try_filled(width, height, fill)?;
+        let rows = self
+            .cells
+            .range([window.min[1], window.min[0]]..=[window.max[1], window.max[0]]);
+        for (&[y, x], cell) in rows {
+            if window.contains([x, y]) {
+                let pos = ((x - window.min[0]) as usize, (y - window.min[1]) as usize);
+                grid[pos] = cell.clone();
+            }
+        }
+        Ok(grid)
+    }
+}
+
+impl<T, const N: usize> Default for SparseGrid<T, N> {
+    fn default() -> Self {
+        SparseGrid::new()
+    }
+}
+
+impl<T, const N: usize> Extend<([i64; N], T)> for SparseGrid<T, N> {
+    fn extend<I: IntoIterator<Item = ([i64; N], T)>>(&mut self, iter: I) {
+        for (point, value) in iter {
+            self.insert(point, value);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<([i64; N], T)> for SparseGrid<T, N> {
+    fn from_iter<I: IntoIterator<Item = ([i64; N], T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+/// Returns every offset to a cell sharing an edge with the origin.
+pub fn cardinal_offsets<const N: usize>() -> Vec<[i64; N]> {
+    (0..N)
+        .flat_map(|axis| {
+            [-1, 1].map(|step| {
+                let mut offset = [0; N];
+                offset[axis] = step;
+                offset
+            })
+        })
+        .collect()
+}
+
+/// Returns every offset to a cell touching the origin, even at a corner.
+pub fn adjacent_offsets<const N: usize>() -> Vec<[i64; N]> {
+    let count = 3usize.pow(N as u32);
+    (0..count)
+        .map(|mut idx| {
+            std::array::from_fn(|_| {
+                let step = (idx % 3) as i64 - 1;
+                idx /= 3;
+                step
+            })
+        })
+        .filter(|offset: &[i64; N]| offset.iter().any(|&step| step != 0))
+        .collect()
+}
+
+/// Reverse the axes of a point, which turns it into a key and back.
+fn key<const N: usize>(mut point: [i64; N]) -> [i64; N] {
+    point.reverse();
+    point
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert([3, -2], 'a');
+        grid.insert([-10, 5], 'b');
+        grid.insert([20_000, 1], 'c');
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds {
+                min: [-10, -2],
+                max: [20_000, 5]
+            })
+        );
+
+        grid.remove([20_000, 1]);
+        assert_eq!(grid.bounds().map(|b| b.max), Some([3, 5]));
+        assert_eq!(grid.bounds().and_then(|b| b.size()), Some([14, 8]));
+        assert_eq!(grid.len(), 2);
+
+        grid.insert([i64::MIN, 0], 'd');
+        assert_eq!(grid.bounds().and_then(|b| b.size()), Some([1 << 63 | 4, 8]));
+        grid.insert([i64::MAX, 0], 'e');
+        assert_eq!(grid.bounds().and_then(|b| b.size()), None);
+        let inverted = Bounds {
+            min: [1, 0],
+            max: [0, 0],
+        };
+        assert_eq!(inverted.size(), None);
+    }
+
+    #[test]
+    fn row_major() {
+        let grid = [([1, 1], 'd'), ([5, 0], 'b'), ([-1, 1], 'c'), ([0, 0], 'a')]
+            .into_iter()
+            .collect::<SparseGrid<_>>();
+
+        assert_eq!(grid.iter().map(|(_, &ch)| ch).collect::<String>(), "abcd");
+        assert_eq!(grid.points().next(), Some([0, 0]));
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = [([0, 0], 1), ([1, 0], 2), ([1, 1], 3), ([5, 5], 4)]
+            .into_iter()
+            .collect::<SparseGrid<_>>();
+
+        assert_eq!(cardinal_offsets::<2>().len(), 4);
+        assert_eq!(adjacent_offsets::<2>().len(), 8);
+        assert_eq!(
+            grid.neighbors([0, 0], &adjacent_offsets()).count(),
+            2,
+            "diagonal neighbor should count"
+        );
+        assert_eq!(grid.neighbors([0, 0], &cardinal_offsets()).count(), 1);
+
+        let edge = [([i64::MAX, 0], 1), ([i64::MAX - 1, 0], 2)]
+            .into_iter()
+            .collect::<SparseGrid<_>>();
+        assert_eq!(
+            edge.neighbors([i64::MAX, 0], &adjacent_offsets()).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn three_dimensions() {
+        let mut grid = SparseGrid3::new();
+        grid.insert([162, 817, 812], ());
+        grid.insert([57, 618, 57], ());
+        grid.insert([57, 618, 58], ());
+
+        assert_eq!(adjacent_offsets::<3>().len(), 26);
+        assert_eq!(
+            grid.neighbors([57, 618, 57], &cardinal_offsets()).count(),
+            1
+        );
+        assert_eq!(grid.points().next(), Some([57, 618, 57]));
+        assert_eq!(grid.bounds().and_then(|b| b.size()), Some([106, 200, 756]));
+    }
+
+    #[test]
+    fn dense_window() {
+        let grid = [([-1, -1], '#'), ([1, 0], '#'), ([40, 0], '#')]
+            .into_iter()
+            .collect::<SparseGrid<_>>();
+
+        let dense = grid
+            .to_grid(
+                Bounds {
+                    min: [-1, -1],
+                    max: [1, 0],
+                },
+                '.',
+            )
+            .expect("window is valid");
+        assert_eq!(dense.to_string(), "#..\n..#\n");
+
+        let inverted = Bounds {
+            min: [1, 0],
+            max: [-1, 0],
+        };
+        assert!(grid.to_grid(inverted, '.').is_err());
+        let huge = Bounds {
+            min: [i64::MIN, 0],
+            max: [i64::MAX, 0],
+        };
+        assert!(grid.to_grid(huge, '.').is_err());
+        let wide = Bounds {
+            min: [i64::MIN, 0],
+            max: [i64::MAX - 1, 0],
+        };
+        assert!(grid.to_grid(wide, '.').is_err());
+    }
+}
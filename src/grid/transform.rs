@@ -0,0 +1,360 @@
+//! Rotating, flipping and cutting up a [Grid] without copying it.
+
+use std::fmt;
+use std::ops::Index;
+
+use super::{Grid, Position};
+
+/// One of the eight ways to turn and flip a rectangle onto itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    /// A quarter turn clockwise.
+    Rotate90,
+    Rotate180,
+    /// A quarter turn counterclockwise.
+    Rotate270,
+    /// Mirror left to right.
+    FlipHorizontal,
+    /// Mirror top to bottom.
+    FlipVertical,
+    /// Mirror across the diagonal from the top left corner.
+    Transpose,
+    /// Mirror across the diagonal from the top right corner.
+    AntiTranspose,
+}
+
+impl Symmetry {
+    /// Every symmetry, starting with the identity.
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    /// Returns the map from a position in the result back to a position
+    /// in the original, given the original's dimensions.
+    ///
+    /// The map takes `(x, y)` to `origin + axes * (x, y)`.
+    fn affine(self, (width, height): (usize, usize)) -> ((isize, isize), [[isize; 2]; 2]) {
+        let right = width as isize - 1;
+        let bottom = height as isize - 1;
+        match self {
+            Symmetry::Identity => ((0, 0), [[1, 0], [0, 1]]),
+            Symmetry::Rotate90 => ((0, bottom), [[0, 1], [-1, 0]]),
+            Symmetry::Rotate180 => ((right, bottom), [[-1, 0], [0, -1]]),
+            Symmetry::Rotate270 => ((right, 0), [[0, -1], [1, 0]]),
+            Symmetry::FlipHorizontal => ((right, 0), [[-1, 0], [0, 1]]),
+            Symmetry::FlipVertical => ((0, bottom), [[1, 0], [0, -1]]),
+            Symmetry::Transpose => ((0, 0), [[0, 1], [1, 0]]),
+            Symmetry::AntiTranspose => ((right, bottom), [[0, -1], [-1, 0]]),
+        }
+    }
+
+    /// Returns true if this swaps the width and the height.
+    fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Symmetry::Rotate90
+                | Symmetry::Rotate270
+                | Symmetry::Transpose
+                | Symmetry::AntiTranspose
+        )
+    }
+}
+
+/// A borrowed, possibly transformed, rectangular window onto a [Grid].
+///
+/// Nothing is copied until [View::to_grid] is called.
+#[derive(Debug)]
+pub struct View<'a, T> {
+    grid: &'a Grid<T>,
+    width: usize,
+    height: usize,
+    // A position (x, y) in the view is (origin + axes * (x, y)) in the grid.
+    origin: (isize, isize),
+    axes: [[isize; 2]; 2],
+}
+
+// Deriving these would require `T: Clone`, which isn't needed for a reference.
+impl<T> Clone for View<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for View<'_, T> {}
+
+impl<T> Grid<T> {
+    /// Get a view of the whole grid.
+    pub fn view(&self) -> View<'_, T> {
+        View {
+            grid: self,
+            width: self.width,
+            height: self.height,
+            origin: (0, 0),
+            axes: [[1, 0], [0, 1]],
+        }
+    }
+
+    /// Get a view of a rectangular part of the grid.
+    ///
+    /// Returns `None` if the rectangle doesn't fit inside of the grid.
+    pub fn subgrid(&self, pos: Position, width: usize, height: usize) -> Option<View<'_, T>> {
+        self.view().subgrid(pos, width, height)
+    }
+
+    /// Returns every distinct way the grid can be turned or flipped.
+    ///
+    /// Each grid comes with the first symmetry that produces it, so a
+    /// pattern with no symmetry of its own gives eight results and
+    /// a single cell gives only one.
+    pub fn symmetries(&self) -> Vec<(Symmetry, Grid<T>)>
+    where
+        T: Clone + PartialEq,
+    {
+        let mut found: Vec<(Symmetry, Grid<T>)> = Vec::new();
+        for symmetry in Symmetry::ALL {
+            let grid = self.view().transform(symmetry).to_grid();
+            if found.iter().all(|(_, other)| *other != grid) {
+                found.push((symmetry, grid));
+            }
+        }
+        found
+    }
+}
+
+impl<'a, T> View<'a, T> {
+    /// Returns the number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `(width, height)`.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Get the cell at a position in the view, if it exists.
+    pub fn get(&self, pos: Position) -> Option<&'a T> {
+        self.source(pos).map(|pos| &self.grid[pos])
+    }
+
+    /// Turn or flip the view.
+    pub fn transform(&self, symmetry: Symmetry) -> View<'a, T> {
+        let (origin, axes) = symmetry.affine(self.dimensions());
+        let (width, height) = if symmetry.swaps_axes() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+        View {
+            grid: self.grid,
+            width,
+            height,
+            origin: self.apply(origin),
+            axes: multiply(self.axes, axes),
+        }
+    }
+
+    /// Turn the view a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> View<'a, T> {
+        self.transform(Symmetry::Rotate90)
+    }
+
+    /// Turn the view a quarter turn counterclockwise.
+    pub fn rotate_ccw(&self) -> View<'a, T> {
+        self.transform(Symmetry::Rotate270)
+    }
+
+    /// Turn the view upside down.
+    pub fn rotate_180(&self) -> View<'a, T> {
+        self.transform(Symmetry::Rotate180)
+    }
+
+    /// Mirror the view left to right.
+    pub fn flip_horizontal(&self) -> View<'a, T> {
+        self.transform(Symmetry::FlipHorizontal)
+    }
+
+    /// Mirror the view top to bottom.
+    pub fn flip_vertical(&self) -> View<'a, T> {
+        self.transform(Symmetry::FlipVertical)
+    }
+
+    /// Swap the rows and columns of the view.
+    pub fn transpose(&self) -> View<'a, T> {
+        self.transform(Symmetry::Transpose)
+    }
+
+    /// Get a view of a rectangular part of this view.
+    ///
+    /// Returns `None` if the rectangle doesn't fit inside of the view.
+    pub fn subgrid(&self, (x, y): Position, width: usize, height: usize) -> Option<View<'a, T>> {
+        if x.checked_add(width)? > self.width || y.checked_add(height)? > self.height {
+            return None;
+        }
+        Some(View {
+            grid: self.grid,
+            width,
+            height,
+            origin: self.apply((x as isize, y as isize)),
+            axes: self.axes,
+        })
+    }
+
+    /// Iterate over a single row of the view.
+    ///
+    /// # Panics
+    /// Panics if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator {
+        assert!(y < self.height, "row {0} out of bounds", y);
+        let view = *self;
+        (0..self.width).map(move |x| view.get((x, y)).expect("inside of the view"))
+    }
+
+    /// Iterate over every row of the view, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &'a T>> {
+        let view = *self;
+        (0..self.height).map(move |y| view.row(y))
+    }
+
+    /// Iterate over every cell of the view in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> {
+        self.rows().flatten()
+    }
+
+    /// Copy the view out into a grid of its own.
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().cloned().collect(),
+        }
+    }
+
+    fn apply(&self, (x, y): (isize, isize)) -> (isize, isize) {
+        let [[a, b], [c, d]] = self.axes;
+        (self.origin.0 + a * x + b * y, self.origin.1 + c * x + d * y)
+    }
+
+    fn source(&self, (x, y): Position) -> Option<Position> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let (x, y) = self.apply((x as isize, y as isize));
+        Some((x as usize, y as usize))
+    }
+}
+
+fn multiply(one: [[isize; 2]; 2], two: [[isize; 2]; 2]) -> [[isize; 2]; 2] {
+    std::array::from_fn(|row| {
+        std::array::from_fn(|col| one[row][0] * two[0][col] + one[row][1] * two[1][col])
+    })
+}
+
+impl<T> Index<Position> for View<'_, T> {
+    type Output = T;
+
+    fn index(&self, pos: Position) -> &Self::Output {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!("position {0:?} out of bounds", pos),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for View<'_, T> {
+    /// Write out every row on its own line, the same as a [Grid].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{0}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse("abc\ndef\n", Ok).unwrap()
+    }
+
+    #[test]
+    fn rotations() {
+        let grid = sample();
+
+        assert_eq!(grid.view().rotate_cw().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.view().rotate_180().to_string(), "fed\ncba\n");
+        assert_eq!(grid.view().rotate_ccw().to_string(), "cf\nbe\nad\n");
+        assert_eq!(
+            grid.view().rotate_cw().rotate_cw().rotate_cw().to_grid(),
+            grid.view().rotate_ccw().to_grid()
+        );
+    }
+
+    #[test]
+    fn flips() {
+        let grid = sample();
+
+        assert_eq!(grid.view().flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(grid.view().flip_vertical().to_string(), "def\nabc\n");
+        assert_eq!(grid.view().transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(
+            grid.view().transform(Symmetry::AntiTranspose).to_string(),
+            "fc\neb\nda\n"
+        );
+        assert_eq!(grid.view().transpose().dimensions(), (2, 3));
+    }
+
+    #[test]
+    fn subgrids() {
+        let grid = sample();
+
+        let corner = grid.subgrid((1, 0), 2, 2).unwrap();
+        assert_eq!(corner.to_string(), "bc\nef\n");
+        assert_eq!(corner.rotate_cw().to_string(), "eb\nfc\n");
+        assert_eq!(
+            corner
+                .rotate_cw()
+                .subgrid((0, 1), 2, 1)
+                .unwrap()
+                .to_string(),
+            "fc\n"
+        );
+        assert_eq!(corner.get((2, 0)), None);
+        assert!(grid.subgrid((2, 0), 2, 1).is_none());
+    }
+
+    #[test]
+    fn symmetries() {
+        let grid = sample();
+        assert_eq!(grid.symmetries().len(), 8);
+
+        let square = Grid::parse("#.\n.#\n", Ok).unwrap();
+        let found = square.symmetries();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].0, Symmetry::Rotate90);
+
+        let cell = Grid::parse("#\n", Ok).unwrap();
+        assert_eq!(cell.symmetries().len(), 1);
+    }
+}
@@ -1,9 +1,13 @@
 //! Helper systems for Advent of Code puzzles.
 
+use std::fmt::Display;
 use std::io::Error as IOError;
 use std::fs::read_to_string;
 
 use thiserror::Error;
+pub mod grid;
+pub mod range;
+pub use range::{MultiRange, MultiRangeBuf, RangeMap};
 pub use simple_grid::{Grid, GridIndex};
 
 #[derive(Error, Debug)]
@@ -19,7 +23,12 @@ pub enum AdventError {
     Data(String),
 }
 
-pub trait Puzzle: Sized {
+/// A puzzle, as represented by its parsed input.
+///
+/// The [Display] implementation is the canonical serializer for the
+/// puzzle's input format, so `parse_input(&x.to_string())` should always
+/// give back something equal to `x`.
+pub trait Puzzle: Sized + Display {
     fn parse_input(file: &str) -> Result<Self, AdventError>;
 
     fn part_one(&self) -> Result<String, AdventError>;
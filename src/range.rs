@@ -1,44 +1,67 @@
 //! An implementation of a multirange for AoC.
 
+use std::cmp;
 use std::iter::Sum;
 use std::mem;
-use std::ops::{Add, Range, Sub};
+use std::ops::{
+    Add, BitAnd, BitXor, Bound, Not, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo,
+    RangeToInclusive, Sub,
+};
 
-use merging_iterator::MergeIter;
+use itertools::Itertools;
 
-/// A MultiRange represents a set of a totally ordered type T, where inclusion in the set
-/// is defined by being within certain bounds. For example, `MultiRange::from(0..2) + MultiRange::from(5..8)`
+mod boxes;
+mod buf;
+mod domain;
+mod iter;
+mod map;
+mod mapping;
+mod text;
+
+pub use boxes::BoxSet;
+pub use buf::MultiRangeBuf;
+pub use domain::{Domain, RangeLike};
+pub use iter::{Boundary, Elements, Window};
+pub use map::RangeMap;
+pub use mapping::Mapping;
+pub use text::RangeFormat;
+
+/// A MultiRange represents a set of a discrete type T, where inclusion in the set
+/// is defined by being within certain bounds. For example, `&*MultiRange::from_range(0..2) + (5..8)`
 /// represents the integer set {0, 1, 5, 6, 7}.
-/// At this time, all ranges must be bounded and half-open (ie. they must be a std::ops::Range).
+/// Any [RangeLike] can be added, and they're all stored as inclusive ranges, so that ones
+/// running all the way up to `T::MAX` don't overflow.
 // We can safely derive PartialEq and Hash because the invariant ensures all equivalent values
 // have the same representation.
 #[derive(Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct MultiRange<T> {
-    // Invariant: ranges are non-overlapping and sorted by start point
-    ranges: [Range<T>],
+    // Invariant: ranges are non-empty, sorted by start point, and neither overlap nor touch
+    ranges: [RangeInclusive<T>],
 }
 
 impl<T> MultiRange<T> {
-    // Invariant: Slice must contain non-overlapping ranges sorted by start point
-    const fn from_slice(slice: &[Range<T>]) -> &MultiRange<T> {
-        // SAFETY: MultiRange<T> is repr(transparent) and contains only a [Range<T>]
-        // so &MultiRange<T> is guarenteed to have the same layout as &[Range<T>]
+    // Invariant: Slice must contain non-empty ranges sorted by start point,
+    // which neither overlap nor touch
+    const fn from_slice(slice: &[RangeInclusive<T>]) -> &MultiRange<T> {
+        // SAFETY: MultiRange<T> is repr(transparent) and contains only a [RangeInclusive<T>]
+        // so &MultiRange<T> is guarenteed to have the same layout as &[RangeInclusive<T>]
         // Invariant: Upheld by caller.
         unsafe { mem::transmute(slice) }
     }
 
-    // Invariant: Slice must contain non-overlapping ranges sorted by start point
-    const fn from_boxed_slice(slice: Box<[Range<T>]>) -> Box<MultiRange<T>> {
-        // SAFETY: MultiRange<T> is repr(transparent) and contains only a [Range<T>]
-        // so Box<MultiRange<T>> is guarenteed to have the same layout as Box<[Range<T>]>
+    // Invariant: Slice must contain non-empty ranges sorted by start point,
+    // which neither overlap nor touch
+    const fn from_boxed_slice(slice: Box<[RangeInclusive<T>]>) -> Box<MultiRange<T>> {
+        // SAFETY: MultiRange<T> is repr(transparent) and contains only a [RangeInclusive<T>]
+        // so Box<MultiRange<T>> is guarenteed to have the same layout as Box<[RangeInclusive<T>]>
         // Invariant: Upheld by caller.
         unsafe { mem::transmute(slice) }
     }
 
-    const fn into_boxed_slice(self: Box<Self>) -> Box<[Range<T>]> {
-        // SAFETY: MultiRange<T> is repr(transparent) and contains only a [Range<T>]
-        // so Box<MultiRange<T>> is guarenteed to have the same layout as Box<[Range<T>]>
+    const fn into_boxed_slice(self: Box<Self>) -> Box<[RangeInclusive<T>]> {
+        // SAFETY: MultiRange<T> is repr(transparent) and contains only a [RangeInclusive<T>]
+        // so Box<MultiRange<T>> is guarenteed to have the same layout as Box<[RangeInclusive<T>]>
         unsafe { mem::transmute(self) }
     }
 
@@ -59,21 +82,75 @@ impl<'a, T: 'a> MultiRange<T> {
     pub const EMPTY: &'a Self = MultiRange::from_slice(&[]);
 }
 
-impl<T: Ord> MultiRange<T> {
+impl<T: Domain> MultiRange<T> {
+    /// Create a MultiRange that is equivalent to a single range of any kind.
+    pub fn from_range<R: RangeLike<T>>(range: R) -> Box<MultiRange<T>> {
+        // Invariant check: A list of at most one non-empty range cannot contain
+        // overlaps and is always sorted.
+        let ranges = range.to_inclusive().into_iter().collect();
+        MultiRange::from_boxed_slice(ranges)
+    }
+
     /// Returns whether a given value is within the multirange
     pub fn contains(&self, value: T) -> bool {
-        for range in self {
-            if range.end <= value {
-                // We're past where the value should be
-                return false;
-            }
-            if range.start <= value {
-                // Start is before us but end is after us, we're found
-                return true;
-            }
+        self.range_containing(value).is_some()
+    }
+
+    /// Returns the component range that a given value is in, if any.
+    pub fn range_containing(&self, value: T) -> Option<&RangeInclusive<T>> {
+        // Ranges are sorted and don't overlap, so their ends are sorted too.
+        // The first one ending at or after the value is the only one that can hold it.
+        let idx = self.ranges.partition_point(|range| *range.end() < value);
+        self.ranges.get(idx).filter(|range| *range.start() <= value)
+    }
+
+    /// Returns the total length of all component ranges.
+    ///
+    /// This is a `u128` so that even a MultiRange covering
+    /// every `u64` can be counted.
+    pub fn size(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|range| T::count(*range.start(), *range.end()))
+            .sum()
+    }
+
+    /// Returns how many members of the MultiRange are less than `value`.
+    ///
+    /// The answer is binary searched, but the size of every range has to be
+    /// counted first, so use [MultiRange::ranks] when making lots of queries.
+    pub fn rank(&self, value: T) -> u128 {
+        self.ranks().rank(value)
+    }
+
+    /// Returns the `k`th smallest member of the MultiRange, counting from zero.
+    ///
+    /// The answer is binary searched, but the size of every range has to be
+    /// counted first, so use [MultiRange::ranks] when making lots of queries.
+    pub fn select(&self, k: u128) -> Option<T> {
+        self.ranks().select(k)
+    }
+
+    /// Count the members before each component range ahead of time,
+    /// so that [rank](Ranks::rank) and [select](Ranks::select)
+    /// only need a binary search.
+    pub fn ranks(&self) -> Ranks<'_, T> {
+        let mut before = Vec::with_capacity(self.ranges.len());
+        let mut total = 0;
+        for range in &self.ranges {
+            before.push(total);
+            total += T::count(*range.start(), *range.end());
+        }
+        Ranks {
+            ranges: self,
+            before,
+            total,
         }
-        // We're past the end of the last range
-        false
+    }
+
+    /// Returns every value within `bounds` that isn't in the MultiRange.
+    pub fn complement<R: RangeLike<T>>(&self, bounds: R) -> Box<MultiRange<T>> {
+        &*MultiRange::from_range(bounds) - self
     }
 }
 
@@ -81,38 +158,63 @@ impl<T: Clone> ToOwned for MultiRange<T> {
     type Owned = Box<MultiRange<T>>;
 
     fn to_owned(&self) -> Self::Owned {
-        let boxed_slice: Box<[Range<T>]> = self.ranges.to_vec().into_boxed_slice();
+        let boxed_slice: Box<[RangeInclusive<T>]> = self.ranges.to_vec().into_boxed_slice();
         MultiRange::from_boxed_slice(boxed_slice)
     }
 }
 
-impl<T, A> MultiRange<T>
-where
-    T: Ord + Copy + Sub<T, Output = A>,
-    A: std::iter::Sum,
-{
+/// A [MultiRange] with the number of members before each component range
+/// counted ahead of time, for logarithmic rank and select queries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ranks<'a, T> {
+    ranges: &'a MultiRange<T>,
+    // Invariant: before[i] is the size of every range before ranges[i]
+    before: Vec<u128>,
+    total: u128,
+}
+
+impl<T: Domain> Ranks<'_, T> {
     /// Returns the total length of all component ranges.
-    pub fn size(&self) -> A {
-        self.ranges.iter().map(|r| r.end - r.start).sum()
+    pub fn size(&self) -> u128 {
+        self.total
+    }
+
+    /// Returns how many members of the MultiRange are less than `value`.
+    pub fn rank(&self, value: T) -> u128 {
+        let ranges = &self.ranges.ranges;
+        let idx = ranges.partition_point(|range| *range.end() < value);
+        match ranges.get(idx) {
+            Some(range) if *range.start() < value => {
+                // The range starts before value, so value can't be the minimum.
+                let before = value.predecessor().expect("value is above the start");
+                self.before[idx] + T::count(*range.start(), before)
+            }
+            Some(_) => self.before[idx],
+            None => self.total,
+        }
     }
-}
 
-impl<'a, T> From<&'a Range<T>> for &'a MultiRange<T> {
-    /// Create a MultiRange that is equivalent to a single Range.
-    fn from(range: &'a Range<T>) -> &'a MultiRange<T> {
-        let ranges = std::slice::from_ref(range);
-        // Invariant check: A single-element list cannot contain overlaps and is always sorted.
-        MultiRange::from_slice(ranges)
+    /// Returns the `k`th smallest member of the MultiRange, counting from zero.
+    pub fn select(&self, k: u128) -> Option<T> {
+        if k >= self.total {
+            return None;
+        }
+        // The last range with no more than k members before it holds the answer.
+        let idx = self.before.partition_point(|&before| before <= k) - 1;
+        self.ranges.ranges[idx]
+            .start()
+            .forward(k - self.before[idx])
     }
 }
 
 // &MultiRange + &MultiRange
-impl<T: Ord + Clone> Add for &MultiRange<T> {
+impl<T: Domain> Add for &MultiRange<T> {
     type Output = Box<MultiRange<T>>;
 
-    // TODO: Can I remove this dependency?
     fn add(self, other: Self) -> Self::Output {
-        let mut iter = MergeIter::with_custom_ordering(self, other, |a, b| b.start < a.start);
+        let mut iter = self
+            .into_iter()
+            .merge_by(other, |a, b| a.start() <= b.start());
         let mut current = if let Some(range) = iter.next() {
             range.clone()
         } else {
@@ -120,15 +222,19 @@ impl<T: Ord + Clone> Add for &MultiRange<T> {
         };
         let mut result = Vec::new();
         for next in iter {
-            if current.end < next.start {
-                // Current is entirely before next, so there's a gap.
-                // Emit current and set next as new current.
-                result.push(current);
-                current = next.clone();
-            } else if current.end < next.end {
-                // Next is partially overlapping current.
-                // Extend current to end of next.
-                current.end = next.end.clone();
+            match current.end().successor() {
+                Some(after) if after < *next.start() => {
+                    // Current is entirely before next, so there's a gap.
+                    // Emit current and set next as new current.
+                    result.push(current);
+                    current = next.clone();
+                }
+                _ if current.end() < next.end() => {
+                    // Next is overlapping or touching current.
+                    // Extend current to end of next.
+                    current = *current.start()..=*next.end();
+                }
+                _ => {}
             }
         }
         // Emit the final current range
@@ -137,57 +243,391 @@ impl<T: Ord + Clone> Add for &MultiRange<T> {
     }
 }
 
-// &MultiRange + Range
-impl<T: Ord + Clone> Add<Range<T>> for &MultiRange<T> {
+// &MultiRange & &MultiRange
+impl<T: Domain> BitAnd for &MultiRange<T> {
     type Output = Box<MultiRange<T>>;
 
-    fn add(self, other: Range<T>) -> Self::Output {
-        let as_multirange: &MultiRange<T> = (&other).into();
-        self + as_multirange
+    /// Returns the values that are in both MultiRanges.
+    fn bitand(self, other: Self) -> Self::Output {
+        let mut ours = self.ranges.iter().peekable();
+        let mut theirs = other.ranges.iter().peekable();
+        let mut result = Vec::new();
+        while let (Some(one), Some(two)) = (ours.peek(), theirs.peek()) {
+            let start = cmp::max(*one.start(), *two.start());
+            let end = cmp::min(*one.end(), *two.end());
+            if start <= end {
+                result.push(start..=end);
+            }
+            // Whichever range ends first can't overlap anything else.
+            if one.end() < two.end() {
+                ours.next();
+            } else {
+                theirs.next();
+            }
+        }
+        MultiRange::from_boxed_slice(result.into_boxed_slice())
     }
 }
 
-impl<T: Ord + Clone, V> Sum<V> for Box<MultiRange<T>>
-where
-    for<'a> &'a MultiRange<T>: Add<V, Output = Box<MultiRange<T>>>,
-{
-    fn sum<I: Iterator<Item = V>>(iter: I) -> Self {
-        let mut result: Self = MultiRange::empty_owned();
-        for value in iter {
-            result = &*result + value;
+// &MultiRange - &MultiRange
+impl<T: Domain> Sub for &MultiRange<T> {
+    type Output = Box<MultiRange<T>>;
+
+    /// Returns the values that are in `self` but not in `other`.
+    fn sub(self, other: Self) -> Self::Output {
+        let mut cuts = other.ranges.iter().peekable();
+        let mut result = Vec::new();
+        for range in &self.ranges {
+            // Where what's left of range starts, if there's anything left.
+            let mut start = Some(*range.start());
+            while let (Some(from), Some(cut)) = (start, cuts.peek()) {
+                if *cut.end() < from {
+                    // Cut is entirely before what's left of range.
+                    cuts.next();
+                    continue;
+                }
+                if range.end() < cut.start() {
+                    // Cut is entirely after range, so it might hit the next one.
+                    break;
+                }
+                if from < *cut.start() {
+                    let to = cut.start().predecessor().expect("cut starts above from");
+                    result.push(from..=to);
+                }
+                if range.end() <= cut.end() {
+                    // Cut covers the rest of range, and might cover the next one too.
+                    start = None;
+                    break;
+                }
+                start = cut.end().successor();
+                cuts.next();
+            }
+            if let Some(from) = start {
+                result.push(from..=*range.end());
+            }
         }
-        result
+        MultiRange::from_boxed_slice(result.into_boxed_slice())
+    }
+}
+
+// &MultiRange + Range, &MultiRange & Range, &MultiRange - Range and summing ranges,
+// for every kind of range. These can't be one generic impl over `RangeLike`,
+// since that would overlap with the impls for MultiRanges.
+macro_rules! impl_range_ops {
+    ($($range:ty),*) => {
+        $(
+            impl<T: Domain> Add<$range> for &MultiRange<T> {
+                type Output = Box<MultiRange<T>>;
+
+                fn add(self, other: $range) -> Self::Output {
+                    self + &*MultiRange::from_range(other)
+                }
+            }
+
+            impl<T: Domain> BitAnd<$range> for &MultiRange<T> {
+                type Output = Box<MultiRange<T>>;
+
+                fn bitand(self, other: $range) -> Self::Output {
+                    self & &*MultiRange::from_range(other)
+                }
+            }
+
+            impl<T: Domain> Sub<$range> for &MultiRange<T> {
+                type Output = Box<MultiRange<T>>;
+
+                fn sub(self, other: $range) -> Self::Output {
+                    self - &*MultiRange::from_range(other)
+                }
+            }
+
+            impl<T: Domain> Sum<$range> for Box<MultiRange<T>> {
+                fn sum<I: Iterator<Item = $range>>(iter: I) -> Self {
+                    iter.collect()
+                }
+            }
+        )*
+    };
+}
+
+impl_range_ops!(
+    Range<T>,
+    RangeInclusive<T>,
+    RangeFrom<T>,
+    RangeTo<T>,
+    RangeToInclusive<T>,
+    RangeFull,
+    (Bound<T>, Bound<T>)
+);
+
+// &MultiRange ^ &MultiRange
+impl<T: Domain> BitXor for &MultiRange<T> {
+    type Output = Box<MultiRange<T>>;
+
+    /// Returns the values that are in exactly one of the MultiRanges.
+    fn bitxor(self, other: Self) -> Self::Output {
+        &*(self - other) + &*(other - self)
+    }
+}
+
+// !&MultiRange
+impl<T: Domain> Not for &MultiRange<T> {
+    type Output = Box<MultiRange<T>>;
+
+    /// Returns every value of `T` that isn't in the MultiRange.
+    fn not(self) -> Self::Output {
+        self.complement(..)
+    }
+}
+
+impl<'a, T: Domain> Sum<&'a MultiRange<T>> for Box<MultiRange<T>> {
+    /// Returns the union of every MultiRange, merging all of their ranges at once.
+    fn sum<I: Iterator<Item = &'a MultiRange<T>>>(iter: I) -> Self {
+        iter.flatten().cloned().collect()
+    }
+}
+
+impl<T: Domain> Sum for Box<MultiRange<T>> {
+    /// Returns the union of every MultiRange, merging all of their ranges at once.
+    fn sum<I: Iterator<Item = Box<MultiRange<T>>>>(iter: I) -> Self {
+        iter.flatten().collect()
     }
 }
 
-impl<T: Ord + Clone> FromIterator<Range<T>> for Box<MultiRange<T>> {
+impl<T: Domain, R: RangeLike<T>> FromIterator<R> for Box<MultiRange<T>> {
     /// Create a MultiRange that contains the union of all ranges in the given iterator.
-    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
-        iter.into_iter().sum()
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        iter.into_iter().collect::<MultiRangeBuf<T>>().into_boxed()
     }
 }
 
-impl<T: Ord> IntoIterator for Box<MultiRange<T>> {
-    type Item = Range<T>;
+impl<T> IntoIterator for Box<MultiRange<T>> {
+    type Item = RangeInclusive<T>;
     type IntoIter = std::vec::IntoIter<Self::Item>;
     fn into_iter(self) -> Self::IntoIter {
         self.into_boxed_slice().into_vec().into_iter()
     }
 }
 
-impl<'a, T: Ord> IntoIterator for &'a MultiRange<T> {
-    type Item = &'a Range<T>;
-    type IntoIter = std::slice::Iter<'a, Range<T>>;
+impl<'a, T> IntoIterator for &'a MultiRange<T> {
+    type Item = &'a RangeInclusive<T>;
+    type IntoIter = std::slice::Iter<'a, RangeInclusive<T>>;
     fn into_iter(self) -> Self::IntoIter {
         self.ranges.iter()
     }
 }
 
+impl<'a, T: Domain> From<&'a RangeInclusive<T>> for &'a MultiRange<T> {
+    /// Create a MultiRange that is equivalent to a single RangeInclusive, without copying it.
+    fn from(range: &'a RangeInclusive<T>) -> &'a MultiRange<T> {
+        if range.is_empty() {
+            return MultiRange::EMPTY;
+        }
+        // Invariant check: A single non-empty range cannot contain overlaps and is always sorted.
+        MultiRange::from_slice(std::slice::from_ref(range))
+    }
+}
+
 #[cfg(test)]
-/// TODO: Test this dang thing.
 mod tests {
     use super::*;
 
+    // Half-open ranges are easier to read than inclusive ones.
+    fn ranges(multi: &MultiRange<u64>) -> Vec<Range<u64>> {
+        multi
+            .into_iter()
+            .map(|range| *range.start()..range.end() + 1)
+            .collect()
+    }
+
+    fn multi(ranges: &[Range<u64>]) -> Box<MultiRange<u64>> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn add() {
+        let one = MultiRange::from_range(0u64..2);
+        let two = MultiRange::from_range(5u64..8);
+
+        let sum = &*one + &*two;
+        assert_eq!(ranges(&sum), vec![0..2, 5..8]);
+        assert_eq!(ranges(&(&*sum + (1..6))), vec![0..8]);
+        // Ranges that only touch are joined together.
+        assert_eq!(ranges(&(&*sum + (2..5))), vec![0..8]);
+        assert_eq!(ranges(&(&*sum + (3..4))), vec![0..2, 3..4, 5..8]);
+        assert_eq!(&*sum + MultiRange::EMPTY, sum);
+    }
+
+    #[test]
+    fn sum() {
+        let sum: Box<MultiRange<u64>> = [10..14, 3..5, 16..21, 12..18].into_iter().sum();
+        assert_eq!(ranges(&sum), vec![3..5, 10..21]);
+
+        let collected = [10..14, 3..5, 16..21, 12..18]
+            .into_iter()
+            .collect::<Box<MultiRange<u64>>>();
+        assert_eq!(collected, sum);
+
+        let empty = std::iter::empty::<Range<u64>>().collect::<Box<MultiRange<u64>>>();
+        assert!(empty.is_empty());
+        let nothing = [4..4, Range { start: 9, end: 2 }]
+            .into_iter()
+            .collect::<Box<MultiRange<u64>>>();
+        assert!(nothing.is_empty());
+
+        let parts = [multi(&[10..14, 3..5]), multi(&[16..21, 12..18])];
+        assert_eq!(parts.iter().map(|part| &**part).sum::<Box<_>>(), sum);
+        assert_eq!(parts.into_iter().sum::<Box<_>>(), sum);
+    }
+
+    #[test]
+    fn size() {
+        let multi = [3..5, 10..14, 16..21, 12..18]
+            .into_iter()
+            .collect::<Box<MultiRange<u64>>>();
+        assert_eq!(multi.size(), 13);
+        assert_eq!(MultiRange::<u64>::EMPTY.size(), 0);
+    }
+
+    #[test]
+    fn contains() {
+        let multi = [3..6, 10..15, 16..21]
+            .into_iter()
+            .collect::<Box<MultiRange<u64>>>();
+
+        assert!(!multi.contains(1));
+        assert!(multi.contains(3));
+        assert!(!multi.contains(6));
+        assert!(multi.contains(11));
+        assert!(!multi.contains(15));
+        assert!(multi.contains(20));
+        assert!(!multi.contains(32));
+        assert!(!MultiRange::EMPTY.contains(0));
+    }
+
     #[test]
-    fn it_works() {}
+    fn intersection() {
+        let one = multi(&[0..5, 8..12, 20..30]);
+        let two = multi(&[3..9, 11..22, 25..26, 40..50]);
+
+        assert_eq!(
+            ranges(&(&*one & &*two)),
+            vec![3..5, 8..9, 11..12, 20..22, 25..26]
+        );
+        assert_eq!(ranges(&(&*one & (4..10))), vec![4..5, 8..10]);
+        assert!((&*one & MultiRange::EMPTY).is_empty());
+        assert_eq!(&*one & &*one, one);
+    }
+
+    #[test]
+    fn difference() {
+        let one = multi(&[0..5, 8..12, 20..30]);
+        let two = multi(&[3..9, 11..22, 25..26, 40..50]);
+
+        assert_eq!(ranges(&(&*one - &*two)), vec![0..3, 9..11, 22..25, 26..30]);
+        assert_eq!(ranges(&(&*two - &*one)), vec![5..8, 12..20, 40..50]);
+        assert_eq!(ranges(&(&*one - (2..25))), vec![0..2, 25..30]);
+        assert!((&*one - &*one).is_empty());
+        assert_eq!(&*one - MultiRange::EMPTY, one);
+    }
+
+    #[test]
+    fn symmetric_difference() {
+        let one = multi(&[0..5, 8..12]);
+        let two = multi(&[3..9, 12..14]);
+
+        assert_eq!(ranges(&(&*one ^ &*two)), vec![0..3, 5..8, 9..14]);
+        assert_eq!(&*one ^ &*two, &*two ^ &*one);
+        assert!((&*one ^ &*one).is_empty());
+    }
+
+    #[test]
+    fn complement() {
+        let one = multi(&[3..5, 8..12]);
+
+        assert_eq!(ranges(&one.complement(0..20)), vec![0..3, 5..8, 12..20]);
+        assert_eq!(ranges(&one.complement(4..10)), vec![5..8]);
+        assert!(one.complement(9..11).is_empty());
+        assert_eq!(ranges(&MultiRange::EMPTY.complement(1..2)), vec![1..2]);
+    }
+
+    #[test]
+    fn search() {
+        let multi = multi(&[3..6, 10..15, 16..21]);
+
+        assert_eq!(multi.range_containing(12), Some(&(10..=14)));
+        assert_eq!(multi.range_containing(16), Some(&(16..=20)));
+        assert_eq!(multi.range_containing(15), None);
+        assert_eq!(multi.range_containing(0), None);
+        assert_eq!(MultiRange::<u64>::EMPTY.range_containing(0), None);
+    }
+
+    #[test]
+    fn rank_and_select() {
+        let multi = multi(&[3..6, 10..15, 16..21]);
+        let ranks = multi.ranks();
+
+        for (value, expected) in [
+            (0, 0),
+            (3, 0),
+            (5, 2),
+            (6, 3),
+            (9, 3),
+            (12, 5),
+            (16, 8),
+            (40, 13),
+        ] {
+            assert_eq!(multi.rank(value), expected, "rank of {0}", value);
+            assert_eq!(ranks.rank(value), expected, "rank of {0}", value);
+        }
+        for (k, expected) in [
+            (0, Some(3)),
+            (2, Some(5)),
+            (3, Some(10)),
+            (8, Some(16)),
+            (12, Some(20)),
+            (13, None),
+        ] {
+            assert_eq!(multi.select(k), expected, "select {0}", k);
+            assert_eq!(ranks.select(k), expected, "select {0}", k);
+        }
+        assert_eq!(ranks.size(), multi.size());
+        assert_eq!(MultiRange::<u64>::EMPTY.ranks().select(0), None);
+    }
+
+    #[test]
+    fn other_bounds() {
+        let multi = [3..=5, 8..=u64::MAX]
+            .into_iter()
+            .collect::<Box<MultiRange<u64>>>();
+        assert!(multi.contains(u64::MAX));
+        assert_eq!(multi.size(), 3 + (u64::MAX - 8) as u128 + 1);
+
+        let upper = &*MultiRange::from_range(..4u64) + (10..);
+        assert_eq!(&*upper + (4..=9), MultiRange::from_range(..));
+        assert_eq!(MultiRange::<u64>::from_range(..).size(), 1 << 64);
+        assert_eq!((!&*upper).into_iter().collect::<Vec<_>>(), vec![4..=9]);
+        assert_eq!(
+            !&*MultiRange::<i8>::from_range(..),
+            MultiRange::empty_owned()
+        );
+
+        let single: &MultiRange<u64> = (&(3..=5)).into();
+        assert_eq!(single, &*MultiRange::from_range(3..6));
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty: &MultiRange<u64> = (&(5..=3)).into();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn signed() {
+        let multi = [-5i64..=-1, 0..=3, 10..=12]
+            .into_iter()
+            .collect::<Box<MultiRange<i64>>>();
+
+        assert_eq!(multi.into_iter().collect::<Vec<_>>(), vec![-5..=3, 10..=12]);
+        assert_eq!(
+            MultiRange::<i64>::from_range(..).size(),
+            i64::count(i64::MIN, i64::MAX)
+        );
+    }
 }
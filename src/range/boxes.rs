@@ -0,0 +1,301 @@
+//! Sets of axis-aligned boxes in any number of dimensions.
+
+use std::cmp;
+use std::ops::{Add, BitAnd, RangeInclusive, Sub};
+
+use super::{Domain, MultiRange, RangeLike};
+
+/// A set of points in `N` dimensions, made up of axis-aligned boxes.
+///
+/// This is what a [MultiRange] is to a line, but for rectangles, cuboids and
+/// so on. Each box is an inclusive range along every axis, and the boxes never
+/// overlap, so the volume is just the sum of their volumes. Unlike a MultiRange
+/// the boxes aren't joined back together, so the same set can be split up in
+/// more than one way.
+#[derive(Clone, Debug)]
+pub struct BoxSet<T, const N: usize> {
+    // Invariant: every box is non-empty and no two boxes overlap.
+    boxes: Vec<[RangeInclusive<T>; N]>,
+}
+
+impl<T, const N: usize> BoxSet<T, N> {
+    /// Create an empty BoxSet.
+    pub fn new() -> Self {
+        BoxSet { boxes: Vec::new() }
+    }
+
+    /// Returns true if the BoxSet is empty.
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// Iterate over the disjoint boxes that make up the BoxSet.
+    pub fn iter(&self) -> std::slice::Iter<'_, [RangeInclusive<T>; N]> {
+        self.boxes.iter()
+    }
+}
+
+impl<T: Domain, const N: usize> BoxSet<T, N> {
+    /// Create a BoxSet containing a single box, with one range per axis.
+    pub fn from_box<R: RangeLike<T>>(bounds: [R; N]) -> Self {
+        let mut set = BoxSet::new();
+        set.insert(bounds);
+        set
+    }
+
+    /// Returns whether a point is inside of any box.
+    pub fn contains(&self, point: [T; N]) -> bool {
+        self.boxes.iter().any(|b| {
+            b.iter()
+                .zip(point)
+                .all(|(range, value)| range.contains(&value))
+        })
+    }
+
+    /// Returns how many points are inside of the BoxSet.
+    ///
+    /// This panics if the volume doesn't fit in a `u128`, which can only
+    /// happen for boxes that span most of a 64-bit domain in three or more
+    /// dimensions.
+    pub fn volume(&self) -> u128 {
+        self.boxes
+            .iter()
+            .map(|b| {
+                b.iter()
+                    .map(|range| T::count(*range.start(), *range.end()))
+                    .try_fold(1u128, u128::checked_mul)
+                    .expect("volume fits in a u128")
+            })
+            .sum()
+    }
+
+    /// Add every point in a box.
+    pub fn insert<R: RangeLike<T>>(&mut self, bounds: [R; N]) {
+        let Some(new) = to_box(&bounds) else {
+            return;
+        };
+        self.cut(&new);
+        self.boxes.push(new);
+    }
+
+    /// Remove every point in a box.
+    pub fn remove<R: RangeLike<T>>(&mut self, bounds: [R; N]) {
+        if let Some(cut) = to_box(&bounds) {
+            self.cut(&cut);
+        }
+    }
+
+    /// Split up every box overlapping `cut`, keeping only the pieces outside of it.
+    fn cut(&mut self, cut: &[RangeInclusive<T>; N]) {
+        let mut kept = Vec::with_capacity(self.boxes.len());
+        for mut b in self.boxes.drain(..) {
+            if intersect(&b, cut).is_none() {
+                kept.push(b);
+                continue;
+            }
+            // Peel off the parts below and above the cut one axis at a time,
+            // shrinking what's left until it sits entirely inside of the cut.
+            for axis in 0..N {
+                let (start, end) = (*b[axis].start(), *b[axis].end());
+                let (cut_start, cut_end) = (*cut[axis].start(), *cut[axis].end());
+                if start < cut_start {
+                    let mut below = b.clone();
+                    let before = cut_start.predecessor().expect("cut_start is above start");
+                    below[axis] = start..=before;
+                    kept.push(below);
+                }
+                if cut_end < end {
+                    let mut above = b.clone();
+                    let after = cut_end.successor().expect("cut_end is below end");
+                    above[axis] = after..=end;
+                    kept.push(above);
+                }
+                b[axis] = cmp::max(start, cut_start)..=cmp::min(end, cut_end);
+            }
+        }
+        self.boxes = kept;
+    }
+}
+
+impl<T: Domain> BoxSet<T, 1> {
+    /// Turn a one-dimensional BoxSet into the equivalent MultiRange.
+    pub fn to_multirange(&self) -> Box<MultiRange<T>> {
+        self.boxes.iter().map(|[range]| range.clone()).collect()
+    }
+}
+
+/// Returns the overlap between two boxes, if there is any.
+fn intersect<T: Domain, const N: usize>(
+    one: &[RangeInclusive<T>; N],
+    two: &[RangeInclusive<T>; N],
+) -> Option<[RangeInclusive<T>; N]> {
+    let overlap = std::array::from_fn(|axis| {
+        cmp::max(*one[axis].start(), *two[axis].start())
+            ..=cmp::min(*one[axis].end(), *two[axis].end())
+    });
+    overlap
+        .iter()
+        .all(|range: &RangeInclusive<T>| !range.is_empty())
+        .then_some(overlap)
+}
+
+/// Turn a range along every axis into a box, or `None` if any of them are empty.
+fn to_box<T: Domain, R: RangeLike<T>, const N: usize>(
+    bounds: &[R; N],
+) -> Option<[RangeInclusive<T>; N]> {
+    let ranges = bounds.each_ref().map(|range| range.to_inclusive());
+    ranges
+        .iter()
+        .all(Option::is_some)
+        .then(|| ranges.map(|range| range.expect("every range is non-empty")))
+}
+
+impl<T, const N: usize> Default for BoxSet<T, N> {
+    fn default() -> Self {
+        BoxSet::new()
+    }
+}
+
+impl<T: Domain, const N: usize> PartialEq for BoxSet<T, N> {
+    /// Two BoxSets are equal if they contain the same points,
+    /// no matter how they're split up into boxes.
+    fn eq(&self, other: &Self) -> bool {
+        (self - other).is_empty() && (other - self).is_empty()
+    }
+}
+
+impl<T: Domain, const N: usize> Eq for BoxSet<T, N> {}
+
+// &BoxSet + &BoxSet
+impl<T: Domain, const N: usize> Add for &BoxSet<T, N> {
+    type Output = BoxSet<T, N>;
+
+    /// Returns the points that are in either BoxSet.
+    fn add(self, other: Self) -> Self::Output {
+        // Whatever is left of other after cutting self out of it can't overlap self.
+        let mut result = other.clone();
+        for cut in &self.boxes {
+            result.cut(cut);
+        }
+        result.boxes.extend(self.boxes.iter().cloned());
+        result
+    }
+}
+
+// &BoxSet & &BoxSet
+impl<T: Domain, const N: usize> BitAnd for &BoxSet<T, N> {
+    type Output = BoxSet<T, N>;
+
+    /// Returns the points that are in both BoxSets.
+    fn bitand(self, other: Self) -> Self::Output {
+        // Overlaps between disjoint boxes are disjoint as well.
+        let boxes = self
+            .boxes
+            .iter()
+            .flat_map(|one| other.boxes.iter().filter_map(|two| intersect(one, two)))
+            .collect();
+        BoxSet { boxes }
+    }
+}
+
+// &BoxSet - &BoxSet
+impl<T: Domain, const N: usize> Sub for &BoxSet<T, N> {
+    type Output = BoxSet<T, N>;
+
+    /// Returns the points that are in the first BoxSet but not the second.
+    fn sub(self, other: Self) -> Self::Output {
+        let mut result = self.clone();
+        for cut in &other.boxes {
+            result.cut(cut);
+        }
+        result
+    }
+}
+
+impl<T: Domain, R: RangeLike<T>, const N: usize> Extend<[R; N]> for BoxSet<T, N> {
+    fn extend<I: IntoIterator<Item = [R; N]>>(&mut self, iter: I) {
+        for bounds in iter {
+            self.insert(bounds);
+        }
+    }
+}
+
+impl<T: Domain, R: RangeLike<T>, const N: usize> FromIterator<[R; N]> for BoxSet<T, N> {
+    /// Create a BoxSet that contains the union of all boxes in the given iterator.
+    fn from_iter<I: IntoIterator<Item = [R; N]>>(iter: I) -> Self {
+        let mut set = BoxSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Clone> From<&MultiRange<T>> for BoxSet<T, 1> {
+    fn from(multi: &MultiRange<T>) -> Self {
+        BoxSet {
+            boxes: multi.into_iter().map(|range| [range.clone()]).collect(),
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a BoxSet<T, N> {
+    type Item = &'a [RangeInclusive<T>; N];
+    type IntoIter = std::slice::Iter<'a, [RangeInclusive<T>; N]>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.boxes.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rectangles() {
+        let mut set = BoxSet::from_box([0u64..10, 0..10]);
+        set.insert([5..15, 5..15]);
+        assert_eq!(set.volume(), 100 + 100 - 25);
+        assert!(set.contains([12, 12]));
+        assert!(!set.contains([12, 2]));
+
+        // Punch a hole in the middle.
+        set.remove([5..=9, 5..=9]);
+        assert_eq!(set.volume(), 175 - 25);
+        assert!(!set.contains([7, 7]));
+        assert!(set.contains([3, 7]));
+        assert!(set.contains([14, 14]));
+
+        set.insert([0..=0, 100..=100]);
+        set.insert([3..3, 0..100]);
+        assert_eq!(set.volume(), 151);
+    }
+
+    #[test]
+    fn operations() {
+        let one = [[0i64..=9, 0..=9, 0..=9]]
+            .into_iter()
+            .collect::<BoxSet<_, 3>>();
+        let two = BoxSet::from_box([5..=14, 5..=14, 5..=14]);
+
+        assert_eq!((&one & &two).volume(), 125);
+        assert_eq!((&one - &two).volume(), 1000 - 125);
+        assert_eq!((&one + &two).volume(), 2000 - 125);
+        assert_eq!(&(&one - &two) + &two, &one + &two);
+        assert!((&(&one - &two) & &two).is_empty());
+        assert_ne!(one, two);
+    }
+
+    #[test]
+    fn agrees_with_multirange() {
+        let one: Box<MultiRange<u8>> = [3..=5, 10..=20, 40..=250].into_iter().collect();
+        let two: Box<MultiRange<u8>> = [0..=4, 15..=45, 100..=100].into_iter().collect();
+        let (boxes_one, boxes_two) = (BoxSet::from(&*one), BoxSet::from(&*two));
+
+        assert_eq!((&boxes_one + &boxes_two).to_multirange(), &*one + &*two);
+        assert_eq!((&boxes_one & &boxes_two).to_multirange(), &*one & &*two);
+        assert_eq!((&boxes_one - &boxes_two).to_multirange(), &*one - &*two);
+        assert_eq!(boxes_one.volume(), one.size());
+        for value in 0..=u8::MAX {
+            assert_eq!(boxes_two.contains([value]), two.contains(value));
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! An owned, growable [MultiRange].
+
+use std::cmp;
+use std::mem;
+use std::ops::{Deref, RangeInclusive};
+
+use itertools::Itertools;
+
+use super::{Domain, MultiRange, RangeLike};
+
+/// An owned [MultiRange] that can have ranges added and removed in place.
+///
+/// This is to a [MultiRange] what a `Vec<T>` is to a `[T]`, and derefs to one,
+/// so every query on a MultiRange works on a MultiRangeBuf as well.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MultiRangeBuf<T> {
+    // Invariant: same as MultiRange
+    ranges: Vec<RangeInclusive<T>>,
+}
+
+impl<T> MultiRangeBuf<T> {
+    /// Create an empty MultiRangeBuf.
+    pub fn new() -> Self {
+        MultiRangeBuf { ranges: Vec::new() }
+    }
+
+    /// Remove everything from the MultiRangeBuf.
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+    }
+
+    /// Turn this into a boxed MultiRange, which can't grow but takes up less space.
+    pub fn into_boxed(self) -> Box<MultiRange<T>> {
+        MultiRange::from_boxed_slice(self.ranges.into_boxed_slice())
+    }
+}
+
+impl<T: Domain> MultiRangeBuf<T> {
+    /// Add every value in a range.
+    ///
+    /// This finds where the range goes with a binary search, and merges it
+    /// with any ranges it overlaps or touches.
+    pub fn insert<R: RangeLike<T>>(&mut self, range: R) {
+        let Some(range) = range.to_inclusive() else {
+            return;
+        };
+        let (mut start, mut end) = range.into_inner();
+        // Every range from `first` up to (but not including) `last`
+        // overlaps or touches the new one.
+        let first = self
+            .ranges
+            .partition_point(|range| range.end().successor().is_some_and(|after| after < start));
+        let last = self.ranges.partition_point(|range| match end.successor() {
+            Some(after) => *range.start() <= after,
+            None => true,
+        });
+        if first < last {
+            start = cmp::min(start, *self.ranges[first].start());
+            end = cmp::max(end, *self.ranges[last - 1].end());
+        }
+        self.ranges.splice(first..last, [start..=end]);
+    }
+
+    /// Remove every value in a range.
+    pub fn remove<R: RangeLike<T>>(&mut self, range: R) {
+        let Some(range) = range.to_inclusive() else {
+            return;
+        };
+        let (start, end) = range.into_inner();
+        // Every range from `first` up to (but not including) `last` overlaps the cut.
+        let first = self.ranges.partition_point(|range| *range.end() < start);
+        let last = self.ranges.partition_point(|range| *range.start() <= end);
+        if first >= last {
+            return;
+        }
+        // Only the first and last ranges can stick out past the cut.
+        let mut pieces = Vec::with_capacity(2);
+        let head = self.ranges[first].clone();
+        if *head.start() < start {
+            let before = start
+                .predecessor()
+                .expect("start is above the head's start");
+            pieces.push(*head.start()..=before);
+        }
+        let tail = self.ranges[last - 1].clone();
+        if end < *tail.end() {
+            let after = end.successor().expect("end is below the tail's end");
+            pieces.push(after..=*tail.end());
+        }
+        self.ranges.splice(first..last, pieces);
+    }
+}
+
+impl<T> Default for MultiRangeBuf<T> {
+    fn default() -> Self {
+        MultiRangeBuf::new()
+    }
+}
+
+impl<T> Deref for MultiRangeBuf<T> {
+    type Target = MultiRange<T>;
+
+    fn deref(&self) -> &MultiRange<T> {
+        // Invariant check: MultiRangeBuf keeps the same invariant as MultiRange.
+        MultiRange::from_slice(&self.ranges)
+    }
+}
+
+impl<T: Domain, R: RangeLike<T>> Extend<R> for MultiRangeBuf<T> {
+    /// Add every range in the given iterator.
+    ///
+    /// The new ranges are sorted once and then merged in with the existing
+    /// ones, rather than being inserted one at a time.
+    fn extend<I: IntoIterator<Item = R>>(&mut self, iter: I) {
+        let added = sorted(iter);
+        if added.is_empty() {
+            return;
+        }
+        let existing = mem::take(&mut self.ranges);
+        self.ranges = merge(
+            existing
+                .into_iter()
+                .merge_by(added, |a, b| a.start() <= b.start()),
+        );
+    }
+}
+
+impl<T: Domain, R: RangeLike<T>> FromIterator<R> for MultiRangeBuf<T> {
+    /// Create a MultiRangeBuf that contains the union of all ranges in the given iterator.
+    ///
+    /// The ranges are sorted once and then merged, rather than being
+    /// inserted one at a time.
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        MultiRangeBuf {
+            ranges: merge(sorted(iter)),
+        }
+    }
+}
+
+/// Collect the non-empty ranges, sorted by their start point.
+fn sorted<T: Domain, R: RangeLike<T>, I: IntoIterator<Item = R>>(
+    iter: I,
+) -> Vec<RangeInclusive<T>> {
+    let mut sorted = iter
+        .into_iter()
+        .filter_map(|range| range.to_inclusive())
+        .collect::<Vec<_>>();
+    sorted.sort_unstable_by_key(|range| *range.start());
+    sorted
+}
+
+/// Join together any overlapping or touching ranges, which must be sorted by their start point.
+fn merge<T: Domain, I: IntoIterator<Item = RangeInclusive<T>>>(
+    sorted: I,
+) -> Vec<RangeInclusive<T>> {
+    let sorted = sorted.into_iter();
+    let mut ranges: Vec<RangeInclusive<T>> = Vec::with_capacity(sorted.size_hint().0);
+    for next in sorted {
+        match ranges.last_mut() {
+            // Overlapping or touching the last range, so extend it.
+            Some(last)
+                if last
+                    .end()
+                    .successor()
+                    .is_none_or(|after| *next.start() <= after) =>
+            {
+                if last.end() < next.end() {
+                    *last = *last.start()..=*next.end();
+                }
+            }
+            _ => ranges.push(next),
+        }
+    }
+    ranges
+}
+
+impl<T: Clone> From<&MultiRange<T>> for MultiRangeBuf<T> {
+    fn from(multi: &MultiRange<T>) -> Self {
+        MultiRangeBuf {
+            ranges: multi.ranges.to_vec(),
+        }
+    }
+}
+
+impl<T> From<Box<MultiRange<T>>> for MultiRangeBuf<T> {
+    fn from(multi: Box<MultiRange<T>>) -> Self {
+        MultiRangeBuf {
+            ranges: multi.into_boxed_slice().into_vec(),
+        }
+    }
+}
+
+impl<T> From<MultiRangeBuf<T>> for Box<MultiRange<T>> {
+    fn from(buf: MultiRangeBuf<T>) -> Self {
+        buf.into_boxed()
+    }
+}
+
+impl<T> IntoIterator for MultiRangeBuf<T> {
+    type Item = RangeInclusive<T>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a MultiRangeBuf<T> {
+    type Item = &'a RangeInclusive<T>;
+    type IntoIter = std::slice::Iter<'a, RangeInclusive<T>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(multi: &MultiRange<u64>) -> Vec<RangeInclusive<u64>> {
+        multi.into_iter().cloned().collect()
+    }
+
+    #[test]
+    fn insert() {
+        let mut buf = MultiRangeBuf::new();
+        buf.insert(10u64..=14);
+        buf.insert(3..=4);
+        buf.insert(20..=25);
+        assert_eq!(ranges(&buf), vec![3..=4, 10..=14, 20..=25]);
+
+        // Touching on the left, overlapping on the right.
+        buf.insert(5..=11);
+        assert_eq!(ranges(&buf), vec![3..=14, 20..=25]);
+        buf.insert(15..=19);
+        assert_eq!(ranges(&buf), vec![3..=25]);
+        buf.insert(30..);
+        buf.insert(..=1);
+        assert_eq!(ranges(&buf), vec![0..=1, 3..=25, 30..=u64::MAX]);
+        buf.insert(7..7);
+        assert_eq!(buf.size(), 2 + 23 + (u64::MAX - 30) as u128 + 1);
+    }
+
+    #[test]
+    fn remove() {
+        let mut buf = [0u64..=5, 10..=20, 30..=40]
+            .into_iter()
+            .collect::<MultiRangeBuf<_>>();
+
+        buf.remove(3..=12);
+        assert_eq!(ranges(&buf), vec![0..=2, 13..=20, 30..=40]);
+        buf.remove(15..=16);
+        assert_eq!(ranges(&buf), vec![0..=2, 13..=14, 17..=20, 30..=40]);
+        buf.remove(21..=29);
+        assert_eq!(ranges(&buf), vec![0..=2, 13..=14, 17..=20, 30..=40]);
+        buf.remove(..=13);
+        buf.remove(35..);
+        assert_eq!(ranges(&buf), vec![14..=14, 17..=20, 30..=34]);
+        buf.remove(..);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn bulk() {
+        let input = [12u64..18, 3..5, 10..14, 16..21, 5..6, 30..30];
+        let buf = input.iter().cloned().collect::<MultiRangeBuf<_>>();
+        assert_eq!(ranges(&buf), vec![3..=5, 10..=20]);
+
+        let mut inserted = MultiRangeBuf::new();
+        inserted.extend(input.iter().cloned());
+        assert_eq!(inserted, buf);
+
+        // Extending merges with what's already there.
+        inserted.extend([25..=u64::MAX, 0..=1, 6..=9, 22..=23]);
+        assert_eq!(
+            ranges(&inserted),
+            vec![0..=1, 3..=20, 22..=23, 25..=u64::MAX]
+        );
+        inserted.extend([2..=2, 21..=21, 24..=24]);
+        assert_eq!(ranges(&inserted), vec![0..=u64::MAX]);
+        inserted.extend(Vec::<RangeInclusive<u64>>::new());
+        assert_eq!(inserted.size(), 1 << 64);
+
+        let boxed = input.iter().cloned().collect::<Box<MultiRange<u64>>>();
+        assert_eq!(*boxed, *buf);
+        assert_eq!(MultiRangeBuf::from(boxed).into_boxed(), buf.into_boxed());
+    }
+}
@@ -0,0 +1,151 @@
+//! The kinds of values a [MultiRange](super::MultiRange) can hold.
+
+use std::ops::{
+    Bound, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+};
+
+/// A discrete, bounded type of value, like any of the primitive integers.
+///
+/// Knowing the smallest and largest values means any kind of range can be
+/// stored inclusively, even one that runs all the way up to `MAX`, and
+/// knowing the next value means ranges that touch can be joined together.
+pub trait Domain: Ord + Copy {
+    /// The smallest value.
+    const MIN: Self;
+    /// The largest value.
+    const MAX: Self;
+
+    /// Returns the next value up, unless this is `MAX`.
+    fn successor(self) -> Option<Self>;
+
+    /// Returns the next value down, unless this is `MIN`.
+    fn predecessor(self) -> Option<Self>;
+
+    /// Returns how many values are in `start..=end`.
+    ///
+    /// Every domain fits in a `u128`, even counting all of its values.
+    fn count(start: Self, end: Self) -> u128;
+
+    /// Returns the value `steps` above this one, if there is one.
+    fn forward(self, steps: u128) -> Option<Self>;
+
+    /// Returns the value `by` away from this one, in either direction, if there is one.
+    fn shift(self, by: i128) -> Option<Self>;
+}
+
+macro_rules! impl_domain {
+    ($($t:ty),*) => {
+        $(
+            impl Domain for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn predecessor(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn count(start: Self, end: Self) -> u128 {
+                    if end < start {
+                        return 0;
+                    }
+                    (end as i128 - start as i128) as u128 + 1
+                }
+
+                fn forward(self, steps: u128) -> Option<Self> {
+                    let steps = i128::try_from(steps).ok()?;
+                    <$t>::try_from((self as i128).checked_add(steps)?).ok()
+                }
+
+                fn shift(self, by: i128) -> Option<Self> {
+                    <$t>::try_from((self as i128).checked_add(by)?).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_domain!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Any kind of range over a [Domain], which can be added to a [MultiRange](super::MultiRange).
+///
+/// This is implemented for every range type in [std::ops], as well as
+/// pairs of [Bound]s.
+pub trait RangeLike<T> {
+    /// Turn the range into an inclusive one, or `None` if it's empty.
+    fn to_inclusive(&self) -> Option<RangeInclusive<T>>;
+}
+
+macro_rules! impl_range_like {
+    ($($t:ty),*) => {
+        $(
+            impl<T: Domain> RangeLike<T> for $t {
+                fn to_inclusive(&self) -> Option<RangeInclusive<T>> {
+                    inclusive(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_range_like!(
+    Range<T>,
+    RangeInclusive<T>,
+    RangeFrom<T>,
+    RangeTo<T>,
+    RangeToInclusive<T>,
+    (Bound<T>, Bound<T>)
+);
+
+impl<T: Domain> RangeLike<T> for RangeFull {
+    fn to_inclusive(&self) -> Option<RangeInclusive<T>> {
+        Some(T::MIN..=T::MAX)
+    }
+}
+
+fn inclusive<T: Domain, R: RangeBounds<T>>(range: &R) -> Option<RangeInclusive<T>> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.successor()?,
+        Bound::Unbounded => T::MIN,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end,
+        Bound::Excluded(&end) => end.predecessor()?,
+        Bound::Unbounded => T::MAX,
+    };
+    (start <= end).then_some(start..=end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        assert_eq!(inclusive(&(3u64..7)), Some(3..=6));
+        assert_eq!(inclusive(&(3u64..=u64::MAX)), Some(3..=u64::MAX));
+        assert_eq!((..0u64).to_inclusive(), None);
+        assert_eq!(inclusive(&(5u64..5)), None);
+        assert_eq!(RangeLike::<i8>::to_inclusive(&..), Some(-128..=127));
+        assert_eq!(
+            inclusive(&(Bound::Excluded(u8::MAX), Bound::Unbounded)),
+            None
+        );
+    }
+
+    #[test]
+    fn counting() {
+        assert_eq!(u64::count(0, u64::MAX), 1 << 64);
+        assert_eq!(i64::count(i64::MIN, i64::MAX), 1 << 64);
+        assert_eq!(u8::count(5, 4), 0);
+        assert_eq!(250u8.forward(5), Some(255));
+        assert_eq!(250u8.forward(6), None);
+        assert_eq!((-3i32).forward(5), Some(2));
+        assert_eq!(5u8.shift(-5), Some(0));
+        assert_eq!(5u8.shift(-6), None);
+    }
+}
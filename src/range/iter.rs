@@ -0,0 +1,239 @@
+//! Looking at a [MultiRange] one value at a time, or through a window.
+
+use std::cmp;
+use std::ops::RangeInclusive;
+use std::slice;
+
+use super::{Domain, MultiRange, RangeLike};
+
+/// Where a [MultiRange] switches between containing values and not.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Boundary<T> {
+    /// The first value of a component range.
+    Start(T),
+    /// The last value of a component range.
+    End(T),
+}
+
+impl<T: Domain> MultiRange<T> {
+    /// Returns the smallest value in the MultiRange.
+    pub fn first(&self) -> Option<T> {
+        self.ranges.first().map(|range| *range.start())
+    }
+
+    /// Returns the largest value in the MultiRange.
+    pub fn last(&self) -> Option<T> {
+        self.ranges.last().map(|range| *range.end())
+    }
+
+    /// Iterate over every value in the MultiRange, in order.
+    ///
+    /// Skipping ahead with [Iterator::nth] jumps over whole ranges
+    /// at once, rather than stepping through every value.
+    pub fn elements(&self) -> Elements<'_, T> {
+        Elements {
+            ranges: self.ranges.iter(),
+            current: None,
+        }
+    }
+
+    /// Iterate over the ranges of values missing between the component ranges.
+    ///
+    /// Only the gaps between ranges are included, not anything
+    /// before the first range or after the last one.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.windows(2).map(|pair| {
+            // Ranges never touch, so there's always at least one value between them.
+            let start = pair[0].end().successor().expect("a range comes after");
+            let end = pair[1].start().predecessor().expect("a range comes before");
+            start..=end
+        })
+    }
+
+    /// Iterate over the first and last value of every component range, in order.
+    pub fn boundaries(&self) -> impl Iterator<Item = Boundary<T>> + '_ {
+        self.ranges
+            .iter()
+            .flat_map(|range| [Boundary::Start(*range.start()), Boundary::End(*range.end())])
+    }
+
+    /// Look at only the part of the MultiRange inside of `bounds`, without copying it.
+    pub fn window<R: RangeLike<T>>(&self, bounds: R) -> Window<'_, T> {
+        let Some(bounds) = bounds.to_inclusive() else {
+            return Window {
+                ranges: &[],
+                bounds: None,
+            };
+        };
+        let first = self
+            .ranges
+            .partition_point(|range| range.end() < bounds.start());
+        let last = self
+            .ranges
+            .partition_point(|range| range.start() <= bounds.end());
+        Window {
+            ranges: &self.ranges[first..cmp::max(first, last)],
+            bounds: Some(bounds),
+        }
+    }
+}
+
+/// An iterator over every value in a [MultiRange].
+#[derive(Clone, Debug)]
+pub struct Elements<'a, T> {
+    ranges: slice::Iter<'a, RangeInclusive<T>>,
+    // The next value in the current range, and the end of that range.
+    current: Option<(T, T)>,
+}
+
+impl<T: Domain> Iterator for Elements<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.nth(0)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<T> {
+        let mut n = n as u128;
+        loop {
+            let (value, end) = match self.current {
+                Some(current) => current,
+                None => {
+                    let range = self.ranges.next()?;
+                    (*range.start(), *range.end())
+                }
+            };
+            let remaining = T::count(value, end);
+            if n < remaining {
+                let found = value.forward(n).expect("found is inside of the range");
+                self.current = found
+                    .successor()
+                    .filter(|&next| next <= end)
+                    .map(|next| (next, end));
+                return Some(found);
+            }
+            n -= remaining;
+            self.current = None;
+        }
+    }
+}
+
+/// A view of the part of a [MultiRange] inside of some bounds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Window<'a, T> {
+    // Every range that overlaps the bounds, which may stick out past them.
+    ranges: &'a [RangeInclusive<T>],
+    // `None` if the bounds are empty.
+    bounds: Option<RangeInclusive<T>>,
+}
+
+impl<T: Domain> Window<'_, T> {
+    /// Returns true if nothing in the MultiRange is inside of the window.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Iterate over the component ranges, cut down to fit inside of the window.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().filter_map(|range| {
+            let bounds = self.bounds.as_ref()?;
+            let start = cmp::max(*range.start(), *bounds.start());
+            let end = cmp::min(*range.end(), *bounds.end());
+            Some(start..=end)
+        })
+    }
+
+    /// Returns whether a given value is within both the MultiRange and the window.
+    pub fn contains(&self, value: T) -> bool {
+        self.bounds
+            .as_ref()
+            .is_some_and(|bounds| bounds.contains(&value))
+            && MultiRange::from_slice(self.ranges).contains(value)
+    }
+
+    /// Returns how many values are within both the MultiRange and the window.
+    pub fn size(&self) -> u128 {
+        self.iter()
+            .map(|range| T::count(*range.start(), *range.end()))
+            .sum()
+    }
+
+    /// Returns the smallest value inside of the window.
+    pub fn first(&self) -> Option<T> {
+        self.iter().next().map(|range| *range.start())
+    }
+
+    /// Returns the largest value inside of the window.
+    pub fn last(&self) -> Option<T> {
+        self.iter().last().map(|range| *range.end())
+    }
+
+    /// Copy the window out into a MultiRange of its own.
+    pub fn to_multirange(&self) -> Box<MultiRange<T>> {
+        // Cutting ranges down can't make them overlap or touch.
+        MultiRange::from_boxed_slice(self.iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Box<MultiRange<u64>> {
+        [3..=5, 10..=14, 20..=20].into_iter().collect()
+    }
+
+    #[test]
+    fn elements() {
+        let multi = sample();
+
+        assert_eq!(
+            multi.elements().collect::<Vec<_>>(),
+            vec![3, 4, 5, 10, 11, 12, 13, 14, 20]
+        );
+        assert_eq!(multi.elements().nth(4), Some(11));
+        assert_eq!(multi.elements().nth(9), None);
+        let mut elements = multi.elements();
+        assert_eq!(elements.nth(1), Some(4));
+        assert_eq!(elements.nth(1), Some(10));
+        assert_eq!(elements.nth(4), Some(20));
+        assert_eq!(elements.next(), None);
+
+        let top = MultiRange::from_range(u8::MAX - 1..);
+        assert_eq!(top.elements().collect::<Vec<_>>(), vec![254, 255]);
+    }
+
+    #[test]
+    fn gaps_and_boundaries() {
+        let multi = sample();
+
+        assert_eq!(multi.gaps().collect::<Vec<_>>(), vec![6..=9, 15..=19]);
+        assert_eq!(multi.first(), Some(3));
+        assert_eq!(multi.last(), Some(20));
+        assert_eq!(
+            multi.boundaries().take(3).collect::<Vec<_>>(),
+            vec![Boundary::Start(3), Boundary::End(5), Boundary::Start(10)]
+        );
+        assert_eq!(MultiRange::<u64>::EMPTY.gaps().count(), 0);
+        assert_eq!(MultiRange::<u64>::EMPTY.first(), None);
+    }
+
+    #[test]
+    fn window() {
+        let multi = sample();
+
+        let window = multi.window(4..=12);
+        assert_eq!(window.iter().collect::<Vec<_>>(), vec![4..=5, 10..=12]);
+        assert_eq!(window.size(), 5);
+        assert!(window.contains(11));
+        assert!(!window.contains(13));
+        assert!(!window.contains(3));
+        assert_eq!(window.first(), Some(4));
+        assert_eq!(window.last(), Some(12));
+        assert_eq!(*window.to_multirange(), *(&*multi & (4..=12)));
+
+        assert!(multi.window(15..20).is_empty());
+        assert!(multi.window(12..12).is_empty());
+        assert_eq!(multi.window(..).to_multirange(), multi);
+    }
+}
@@ -0,0 +1,327 @@
+//! A map from ranges of keys to values.
+
+use std::cmp;
+use std::ops::RangeInclusive;
+
+use super::{Domain, MultiRange, RangeLike};
+
+/// A map from ranges of keys to values, like a [MultiRange] with a value on every range.
+///
+/// Ranges that touch and have equal values are always joined together, so
+/// every map has exactly one representation, just like a MultiRange.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RangeMap<K, V> {
+    // Invariant: ranges are non-empty, sorted by start point and don't overlap.
+    // Ranges that touch always have different values.
+    entries: Vec<(RangeInclusive<K>, V)>,
+}
+
+impl<K, V> RangeMap<K, V> {
+    /// Create an empty RangeMap.
+    pub fn new() -> Self {
+        RangeMap {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns true if the RangeMap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of ranges in the RangeMap.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Iterate over every range and its value, in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, (RangeInclusive<K>, V)> {
+        self.entries.iter()
+    }
+}
+
+impl<K: Domain, V> RangeMap<K, V> {
+    /// Returns the value at a key, if there is one.
+    pub fn get(&self, key: K) -> Option<&V> {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    /// Returns the range containing a key, and its value.
+    pub fn get_key_value(&self, key: K) -> Option<(&RangeInclusive<K>, &V)> {
+        let idx = self
+            .entries
+            .partition_point(|(range, _)| *range.end() < key);
+        self.entries
+            .get(idx)
+            .filter(|(range, _)| *range.start() <= key)
+            .map(|(range, value)| (range, value))
+    }
+
+    /// Iterate over the ranges overlapping `bounds`, cut down to fit inside of them.
+    pub fn range<R: RangeLike<K>>(
+        &self,
+        bounds: R,
+    ) -> impl Iterator<Item = (RangeInclusive<K>, &V)> + '_ {
+        let (entries, bounds) = match bounds.to_inclusive() {
+            Some(bounds) => {
+                let (first, last) = self.overlapping(&bounds);
+                (&self.entries[first..last], Some(bounds))
+            }
+            None => (&self.entries[..0], None),
+        };
+        entries.iter().filter_map(move |(range, value)| {
+            let bounds = bounds.as_ref()?;
+            let start = cmp::max(*range.start(), *bounds.start());
+            let end = cmp::min(*range.end(), *bounds.end());
+            Some((start..=end, value))
+        })
+    }
+
+    /// Returns every key that has a value.
+    pub fn keys(&self) -> Box<MultiRange<K>> {
+        self.entries
+            .iter()
+            .map(|(range, _)| range.clone())
+            .collect()
+    }
+
+    /// Returns the indices of the first entry overlapping a range
+    /// and the first entry after it.
+    fn overlapping(&self, range: &RangeInclusive<K>) -> (usize, usize) {
+        let first = self
+            .entries
+            .partition_point(|(r, _)| r.end() < range.start());
+        let last = self
+            .entries
+            .partition_point(|(r, _)| r.start() <= range.end());
+        (first, cmp::max(first, last))
+    }
+}
+
+impl<K: Domain, V: Clone> RangeMap<K, V> {
+    /// Remove the value from every key in a range.
+    pub fn remove<R: RangeLike<K>>(&mut self, range: R) {
+        let Some(range) = range.to_inclusive() else {
+            return;
+        };
+        let (first, last) = self.overlapping(&range);
+        if first == last {
+            return;
+        }
+        let (start, end) = range.into_inner();
+        // Only the first and last ranges can stick out past the cut.
+        let mut pieces = Vec::with_capacity(2);
+        let (head, head_value) = &self.entries[first];
+        if *head.start() < start {
+            let before = start
+                .predecessor()
+                .expect("start is above the head's start");
+            pieces.push((*head.start()..=before, head_value.clone()));
+        }
+        let (tail, tail_value) = &self.entries[last - 1];
+        if end < *tail.end() {
+            let after = end.successor().expect("end is below the tail's end");
+            pieces.push((after..=*tail.end(), tail_value.clone()));
+        }
+        self.entries.splice(first..last, pieces);
+    }
+}
+
+impl<K: Domain, V: Clone + PartialEq> RangeMap<K, V> {
+    /// Give every key in a range a value, replacing whatever was there before.
+    pub fn insert<R: RangeLike<K>>(&mut self, range: R, value: V) {
+        self.insert_with(range, value, |_, new| new.clone());
+    }
+
+    /// Give every key in a range a value, combining it with whatever was there before.
+    ///
+    /// Keys that already had a value get `merge(old, new)`, and keys that
+    /// didn't get `new` as-is.
+    pub fn insert_with<R, F>(&mut self, range: R, value: V, mut merge: F)
+    where
+        R: RangeLike<K>,
+        F: FnMut(&V, &V) -> V,
+    {
+        let Some(range) = range.to_inclusive() else {
+            return;
+        };
+        let (first, last) = self.overlapping(&range);
+        let (start, end) = range.into_inner();
+
+        let mut pieces = Vec::with_capacity(2 * (last - first) + 1);
+        // The first key in the new range that hasn't been given a value yet.
+        let mut next = Some(start);
+        for (old, old_value) in self.entries.drain(first..last) {
+            if *old.start() < start {
+                let before = start.predecessor().expect("start is above the old start");
+                pieces.push((*old.start()..=before, old_value.clone()));
+            }
+            let from = cmp::max(*old.start(), start);
+            let to = cmp::min(*old.end(), end);
+            if let Some(gap) = next.filter(|&gap| gap < from) {
+                let before = from.predecessor().expect("from is above the gap");
+                pieces.push((gap..=before, value.clone()));
+            }
+            pieces.push((from..=to, merge(&old_value, &value)));
+            next = to.successor().filter(|&after| after <= end);
+            if end < *old.end() {
+                let after = end.successor().expect("end is below the old end");
+                pieces.push((after..=*old.end(), old_value));
+            }
+        }
+        if let Some(gap) = next {
+            // Nothing after the last old range can be left over, since it
+            // would have covered the whole end of the new range.
+            let at = pieces.partition_point(|(r, _)| *r.start() < gap);
+            pieces.insert(at, (gap..=end, value));
+        }
+
+        let count = pieces.len();
+        self.entries.splice(first..first, pieces);
+        self.coalesce(first, first + count);
+    }
+
+    /// Join together any touching ranges with equal values from `first` up to
+    /// `last`, as well as the ranges just outside of them.
+    fn coalesce(&mut self, first: usize, last: usize) {
+        let first = first.saturating_sub(1);
+        let last = cmp::min(last + 1, self.entries.len());
+        let mut joined: Vec<(RangeInclusive<K>, V)> = Vec::with_capacity(last - first);
+        for (range, value) in self.entries.drain(first..last) {
+            match joined.last_mut() {
+                Some((prev, prev_value))
+                    if *prev_value == value && prev.end().successor() == Some(*range.start()) =>
+                {
+                    *prev = *prev.start()..=*range.end();
+                }
+                _ => joined.push((range, value)),
+            }
+        }
+        self.entries.splice(first..first, joined);
+    }
+}
+
+impl<K, V> Default for RangeMap<K, V> {
+    fn default() -> Self {
+        RangeMap::new()
+    }
+}
+
+impl<K: Domain, V: Clone + PartialEq, R: RangeLike<K>> Extend<(R, V)> for RangeMap<K, V> {
+    /// Insert every range in order, so later ranges overwrite earlier ones.
+    fn extend<I: IntoIterator<Item = (R, V)>>(&mut self, iter: I) {
+        for (range, value) in iter {
+            self.insert(range, value);
+        }
+    }
+}
+
+impl<K: Domain, V: Clone + PartialEq, R: RangeLike<K>> FromIterator<(R, V)> for RangeMap<K, V> {
+    /// Insert every range in order, so later ranges overwrite earlier ones.
+    fn from_iter<I: IntoIterator<Item = (R, V)>>(iter: I) -> Self {
+        let mut map = RangeMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V> IntoIterator for RangeMap<K, V> {
+    type Item = (RangeInclusive<K>, V);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a RangeMap<K, V> {
+    type Item = &'a (RangeInclusive<K>, V);
+    type IntoIter = std::slice::Iter<'a, (RangeInclusive<K>, V)>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries<V: Clone>(map: &RangeMap<u64, V>) -> Vec<(RangeInclusive<u64>, V)> {
+        map.iter().cloned().collect()
+    }
+
+    #[test]
+    fn overwrite() {
+        let mut map = RangeMap::new();
+        map.insert(0u64..=9, 'a');
+        map.insert(20..=29, 'b');
+        map.insert(5..=24, 'c');
+        assert_eq!(
+            entries(&map),
+            vec![(0..=4, 'a'), (5..=24, 'c'), (25..=29, 'b')]
+        );
+
+        map.insert(10..=12, 'x');
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get(11), Some(&'x'));
+        assert_eq!(map.get(13), Some(&'c'));
+        assert_eq!(map.get(30), None);
+        assert_eq!(map.get_key_value(7), Some((&(5..=9), &'c')));
+    }
+
+    #[test]
+    fn coalesce() {
+        let mut map = RangeMap::new();
+        map.insert(0u64..=4, 1);
+        map.insert(10..=14, 1);
+        map.insert(5..=9, 1);
+        assert_eq!(entries(&map), vec![(0..=14, 1)]);
+
+        map.insert(3..=6, 2);
+        map.insert(3..=6, 1);
+        assert_eq!(entries(&map), vec![(0..=14, 1)]);
+
+        // Touching ranges with different values stay apart.
+        map.insert(15..=20, 2);
+        assert_eq!(entries(&map), vec![(0..=14, 1), (15..=20, 2)]);
+    }
+
+    #[test]
+    fn merge() {
+        // Which of puzzle 05's ranges each ingredient is in.
+        let mut map = RangeMap::new();
+        for (idx, range) in [3u64..=5, 10..=14, 16..=20, 12..=18]
+            .into_iter()
+            .enumerate()
+        {
+            map.insert_with(range, vec![idx], |old, new| {
+                [old.clone(), new.clone()].concat()
+            });
+        }
+
+        assert_eq!(map.get(5), Some(&vec![0]));
+        assert_eq!(map.get(13), Some(&vec![1, 3]));
+        assert_eq!(map.get(15), Some(&vec![3]));
+        assert_eq!(map.get(17), Some(&vec![2, 3]));
+        assert_eq!(map.get(8), None);
+        assert_eq!(map.keys().size(), 14);
+    }
+
+    #[test]
+    fn ranges() {
+        let mut map = [(0u64..10, 'a'), (10..20, 'b'), (30..40, 'c')]
+            .into_iter()
+            .collect::<RangeMap<_, _>>();
+
+        assert_eq!(
+            map.range(5..=32).collect::<Vec<_>>(),
+            vec![(5..=9, &'a'), (10..=19, &'b'), (30..=32, &'c')]
+        );
+        assert_eq!(map.range(20..30).count(), 0);
+        assert_eq!(map.range(5..5).count(), 0);
+
+        map.remove(8..=31);
+        assert_eq!(entries(&map), vec![(0..=7, 'a'), (32..=39, 'c')]);
+        map.remove(..);
+        assert!(map.is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+//! Shifting whole ranges of values at once.
+
+use std::cmp;
+use std::ops::RangeInclusive;
+
+use super::{Domain, MultiRange, RangeLike, RangeMap};
+
+/// A table of `src..src+len -> dst` mappings, each shifting a range of values by some offset.
+///
+/// Values not covered by any mapping are left where they are. Mappings are
+/// checked in the order they were added, so if two of them overlap the
+/// earlier one wins.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Mapping<T> {
+    // The offset every value is moved by. Missing values aren't covered by
+    // any mapping, so aren't moved either, and no value is ever moved out
+    // of the domain.
+    shifts: RangeMap<T, i128>,
+}
+
+impl<T> Mapping<T> {
+    /// Create an empty Mapping, which leaves every value where it is.
+    pub fn new() -> Self {
+        Mapping {
+            shifts: RangeMap::new(),
+        }
+    }
+}
+
+impl<T: Domain> Mapping<T> {
+    /// Move the values in `src` so that its start ends up at `dst`.
+    ///
+    /// Any part of `src` that would be moved past the end of the domain
+    /// is left alone, as is any part already covered by an earlier mapping.
+    pub fn push<R: RangeLike<T>>(&mut self, src: R, dst: T) {
+        let Some(src) = src.to_inclusive() else {
+            return;
+        };
+        let offset = offset(*src.start(), dst);
+        let end = match T::MAX.shift(-offset) {
+            Some(last) => cmp::min(*src.end(), last),
+            None => *src.end(),
+        };
+        // Even an offset of 0 is kept, so later mappings can't claim those values.
+        self.shifts
+            .insert_with(*src.start()..=end, offset, |&old, _| old);
+    }
+
+    /// Returns where a single value ends up.
+    pub fn get(&self, value: T) -> T {
+        let offset = self.shifts.get(value).copied().unwrap_or(0);
+        value
+            .shift(offset)
+            .expect("mappings stay inside of the domain")
+    }
+
+    /// Returns where every value in a MultiRange ends up.
+    ///
+    /// Ranges are split up wherever they cross from one mapping to the
+    /// next, so this takes time in the number of ranges and mappings,
+    /// rather than the number of values.
+    pub fn apply(&self, multi: &MultiRange<T>) -> Box<MultiRange<T>> {
+        multi
+            .into_iter()
+            .flat_map(|range| self.split(range.clone()))
+            .map(|(range, offset)| shift(&range, offset.unwrap_or(0)))
+            .collect()
+    }
+
+    /// Returns a Mapping that does the same thing as this one followed by `next`.
+    pub fn then(&self, next: &Mapping<T>) -> Mapping<T> {
+        let mut shifts = RangeMap::new();
+        for (range, first) in self.split(T::MIN..=T::MAX) {
+            let moved = first.unwrap_or(0);
+            for (image, second) in next.split(shift(&range, moved)) {
+                // Values covered by either table stay covered.
+                if first.is_some() || second.is_some() {
+                    let offset = moved + second.unwrap_or(0);
+                    shifts.insert(shift(&image, -moved), offset);
+                }
+            }
+        }
+        Mapping { shifts }
+    }
+
+    /// Split a range into pieces that are each moved by a single offset,
+    /// which is `None` where no mapping covers them.
+    fn split(&self, range: RangeInclusive<T>) -> Vec<(RangeInclusive<T>, Option<i128>)> {
+        let mut pieces = Vec::new();
+        // The first value in the range that hasn't been covered yet.
+        let mut next = Some(*range.start());
+        for (covered, &offset) in self.shifts.range(range.clone()) {
+            if let Some(gap) = next.filter(|gap| gap < covered.start()) {
+                let before = covered.start().predecessor().expect("a gap comes before");
+                pieces.push((gap..=before, None));
+            }
+            next = covered.end().successor();
+            pieces.push((covered, Some(offset)));
+        }
+        if let Some(gap) = next.filter(|gap| gap <= range.end()) {
+            pieces.push((gap..=*range.end(), None));
+        }
+        pieces
+    }
+}
+
+/// Returns how far `from` has to move to get to `to`.
+fn offset<T: Domain>(from: T, to: T) -> i128 {
+    if from <= to {
+        (T::count(from, to) - 1) as i128
+    } else {
+        -((T::count(to, from) - 1) as i128)
+    }
+}
+
+/// Move both ends of a range by the same offset.
+fn shift<T: Domain>(range: &RangeInclusive<T>, offset: i128) -> RangeInclusive<T> {
+    let start = range.start().shift(offset);
+    let end = range.end().shift(offset);
+    start.expect("mappings stay inside of the domain")
+        ..=end.expect("mappings stay inside of the domain")
+}
+
+impl<T> Default for Mapping<T> {
+    fn default() -> Self {
+        Mapping::new()
+    }
+}
+
+impl<T: Domain, R: RangeLike<T>> Extend<(R, T)> for Mapping<T> {
+    fn extend<I: IntoIterator<Item = (R, T)>>(&mut self, iter: I) {
+        for (src, dst) in iter {
+            self.push(src, dst);
+        }
+    }
+}
+
+impl<T: Domain, R: RangeLike<T>> FromIterator<(R, T)> for Mapping<T> {
+    /// Create a Mapping from `(src, dst)` pairs, in order.
+    fn from_iter<I: IntoIterator<Item = (R, T)>>(iter: I) -> Self {
+        let mut mapping = Mapping::new();
+        mapping.extend(iter);
+        mapping
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeds() -> Box<MultiRange<u64>> {
+        [79..79 + 14, 55..55 + 13].into_iter().collect()
+    }
+
+    #[test]
+    fn apply() {
+        // 50 98 2 and 52 50 48, written as src..src+len -> dst
+        let mapping = [(98..98 + 2, 50u64), (50..50 + 48, 52)]
+            .into_iter()
+            .collect::<Mapping<_>>();
+
+        assert_eq!(mapping.get(79), 81);
+        assert_eq!(mapping.get(99), 51);
+        assert_eq!(mapping.get(10), 10);
+
+        let spread: Box<MultiRange<u64>> = [40..=60, 95..=105].into_iter().collect();
+        let image: Box<MultiRange<u64>> = [40..=62, 97..=105].into_iter().collect();
+        assert_eq!(mapping.apply(&spread), image);
+        let image: Box<MultiRange<u64>> = [81..81 + 14, 57..57 + 13].into_iter().collect();
+        assert_eq!(mapping.apply(&seeds()), image);
+    }
+
+    #[test]
+    fn earlier_wins() {
+        let mut mapping = Mapping::new();
+        mapping.push(0u8..10, 100);
+        mapping.push(5..20, 200);
+        assert_eq!(mapping.get(7), 107);
+        assert_eq!(mapping.get(12), 207);
+
+        // Running off the end of the domain leaves the rest alone.
+        mapping.push(250..=255, 253);
+        assert_eq!(mapping.get(251), 254);
+        assert_eq!(mapping.get(253), 253);
+    }
+
+    #[test]
+    fn earlier_stays_put() {
+        let mut mapping = Mapping::new();
+        mapping.push(0u8..10, 0);
+        mapping.push(5..20, 100);
+        assert_eq!(mapping.get(7), 7);
+        assert_eq!(mapping.get(12), 107);
+
+        let multi: Box<MultiRange<u8>> = [0..=14].into_iter().collect();
+        let image: Box<MultiRange<u8>> = [0..=9, 105..=109].into_iter().collect();
+        assert_eq!(mapping.apply(&multi), image);
+
+        // Composing keeps the values covered, so they still can't be claimed.
+        let mut both = mapping.then(&Mapping::new());
+        both.push(0..5, 50);
+        assert_eq!(both.get(3), 3);
+    }
+
+    #[test]
+    fn compose() {
+        let one = [(0i32..10, 20), (20..30, 0)]
+            .into_iter()
+            .collect::<Mapping<_>>();
+        let two = [(5i32..25, -5), (-10..0, 100)]
+            .into_iter()
+            .collect::<Mapping<_>>();
+        let both = one.then(&two);
+
+        for value in -20..40 {
+            assert_eq!(both.get(value), two.get(one.get(value)));
+        }
+        let multi: Box<MultiRange<i32>> = [-15..=3, 8..=27].into_iter().collect();
+        assert_eq!(both.apply(&multi), two.apply(&one.apply(&multi)));
+        assert_eq!(one.then(&Mapping::new()), one);
+    }
+}
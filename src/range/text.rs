@@ -0,0 +1,182 @@
+//! Reading and writing a [MultiRange] as text, like `11-22,95-115`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Domain, MultiRange, MultiRangeBuf};
+use crate::AdventError;
+
+/// How to read a list of ranges from text.
+///
+/// By default ranges are separated by commas or newlines, the ends of a range
+/// by a dash, and both ends are included, so both `11-22,95-115` and one
+/// `3-5` per line read as expected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeFormat<'a> {
+    separators: &'a [char],
+    dash: &'a str,
+    half_open: bool,
+}
+
+impl<'a> RangeFormat<'a> {
+    /// Create the default RangeFormat.
+    pub fn new() -> Self {
+        RangeFormat {
+            separators: &[',', '\n'],
+            dash: "-",
+            half_open: false,
+        }
+    }
+
+    /// Set the characters that go between ranges.
+    pub fn with_separators(mut self, separators: &'a [char]) -> Self {
+        self.separators = separators;
+        self
+    }
+
+    /// Set the text that goes between the start and end of a range.
+    pub fn with_dash(mut self, dash: &'a str) -> Self {
+        self.dash = dash;
+        self
+    }
+
+    /// Set whether the end of each range is left out, like in `start..end`.
+    pub fn with_half_open(mut self, half_open: bool) -> Self {
+        self.half_open = half_open;
+        self
+    }
+
+    /// Read a list of ranges, merging them together.
+    ///
+    /// Whitespace around each range is ignored, as are blank entries, so a
+    /// trailing newline is fine. A range that ends before it starts is an
+    /// error, though an empty half-open one like `3..3` is not.
+    pub fn parse<T: Domain + FromStr>(&self, text: &str) -> Result<MultiRangeBuf<T>, AdventError> {
+        if self.dash.is_empty() {
+            return Err(AdventError::Data(
+                "ranges need a dash between their ends".to_string(),
+            ));
+        }
+        text.split(self.separators)
+            .map(str::trim)
+            .filter(|range| !range.is_empty())
+            .map(|range| {
+                // Skip the first character, so a leading minus sign isn't mistaken for the dash.
+                let skip = range.chars().next().map_or(0, char::len_utf8);
+                let Some((start, end)) = range[skip..].split_once(self.dash) else {
+                    return Err(AdventError::Parse(format!("invalid range {0}", range)));
+                };
+                let start = &range[..skip + start.len()];
+                let start = start
+                    .trim()
+                    .parse::<T>()
+                    .map_err(|_| AdventError::Parse(format!("invalid range start {0}", start)))?;
+                let end = end
+                    .trim()
+                    .parse::<T>()
+                    .map_err(|_| AdventError::Parse(format!("invalid range end {0}", end)))?;
+                if start > end {
+                    return Err(AdventError::Parse(format!("invalid range {0}", range)));
+                }
+                if self.half_open {
+                    Ok(end.predecessor().map(|end| start..=end))
+                } else {
+                    Ok(Some(start..=end))
+                }
+            })
+            .filter_map(Result::transpose)
+            .collect()
+    }
+}
+
+impl Default for RangeFormat<'_> {
+    fn default() -> Self {
+        RangeFormat::new()
+    }
+}
+
+impl<T: Domain + FromStr> FromStr for Box<MultiRange<T>> {
+    type Err = AdventError;
+
+    /// Read ranges in the default [RangeFormat].
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        RangeFormat::new()
+            .parse(text)
+            .map(MultiRangeBuf::into_boxed)
+    }
+}
+
+impl<T: Domain + FromStr> FromStr for MultiRangeBuf<T> {
+    type Err = AdventError;
+
+    /// Read ranges in the default [RangeFormat].
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        RangeFormat::new().parse(text)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for MultiRange<T> {
+    /// Write the merged ranges inclusively, separated by commas.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, range) in self.ranges.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{0}-{1}", range.start(), range.end())?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for MultiRangeBuf<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let multi = "11-22,95-115,998-1012\n"
+            .parse::<Box<MultiRange<u64>>>()
+            .expect("could not parse ranges");
+        assert_eq!(multi.to_string(), "11-22,95-115,998-1012");
+
+        let lines = "3-5\n10-14\n16-20\n12-18\n\n"
+            .parse::<MultiRangeBuf<u64>>()
+            .expect("could not parse ranges");
+        assert_eq!(lines.to_string(), "3-5,10-20");
+
+        let signed = "-10--5, -4-3".parse::<Box<MultiRange<i32>>>();
+        assert_eq!(signed.expect("could not parse ranges").to_string(), "-10-3");
+
+        assert!(""
+            .parse::<Box<MultiRange<u64>>>()
+            .is_ok_and(|multi| multi.is_empty()));
+        assert!("3-x".parse::<Box<MultiRange<u64>>>().is_err());
+        assert!("3".parse::<Box<MultiRange<u64>>>().is_err());
+        assert!("11-22,20-10".parse::<Box<MultiRange<u64>>>().is_err());
+        assert!("-3--5".parse::<Box<MultiRange<i32>>>().is_err());
+    }
+
+    #[test]
+    fn formats() {
+        let format = RangeFormat::new()
+            .with_separators(&[';'])
+            .with_dash("..")
+            .with_half_open(true);
+        let multi = format
+            .parse::<u8>("0..10; 10..20; 30..30; 40..41")
+            .expect("could not parse ranges");
+        assert_eq!(multi.to_string(), "0-19,40-40");
+
+        let reparsed = multi.to_string().parse::<MultiRangeBuf<u8>>();
+        assert_eq!(reparsed.expect("could not parse ranges"), multi);
+
+        assert!(format.parse::<u8>("20..10").is_err());
+        assert!(RangeFormat::new().with_dash("").parse::<u8>("12").is_err());
+    }
+}